@@ -23,9 +23,9 @@ fn main() {
     for (index, n) in algae.take(8).enumerate() {
         let mut printed = format!("n = {}: ", index);
         for i in n.iter() {
-            match i {
-                &AlgeaState::A => printed.push_str("A"),
-                &AlgeaState::B => printed.push_str("B")
+            match *i {
+                AlgeaState::A => printed.push('A'),
+                AlgeaState::B => printed.push('B')
             }
         }
         println!("{}", printed)
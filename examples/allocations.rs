@@ -0,0 +1,81 @@
+//! Measures the number of heap allocations made while deriving the Koch curve, with rules that
+//! return a fresh `Vec` for every symbol, with rules that return a `Successor`, and with a `Rules`
+//! table.
+
+extern crate lsystem;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use lsystem::LSystem;
+use lsystem::rules::Rules;
+use lsystem::successor::Successor;
+
+// Wrap the system allocator so that every allocation is counted.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Koch {
+    F, Plus, Minus
+}
+
+use Koch::{F, Plus, Minus};
+
+const GENERATIONS: usize = 6;
+
+fn main() {
+    let mut allocating = LSystem::new(vec!(F), |x| match x {
+        F => vec!(F, Plus, F, Minus, F, Minus, F, Plus, F),
+        c => vec!(c)
+    });
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0..GENERATIONS {
+        allocating.step();
+    }
+    let with_vec = ALLOCATIONS.load(Ordering::SeqCst) - before;
+
+    let mut borrowing = LSystem::new(vec!(F), |x| match x {
+        F => Successor::Slice(&[F, Plus, F, Minus, F, Minus, F, Plus, F]),
+        c => Successor::One(c)
+    });
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0..GENERATIONS {
+        borrowing.step();
+    }
+    let with_successor = ALLOCATIONS.load(Ordering::SeqCst) - before;
+
+    let mut table = LSystem::from_rules(vec!(F), Rules::new()
+        .rule(F, vec!(F, Plus, F, Minus, F, Minus, F, Plus, F)));
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0..GENERATIONS {
+        table.step();
+    }
+    let with_rules = ALLOCATIONS.load(Ordering::SeqCst) - before;
+
+    assert!(allocating.current() == borrowing.current());
+    assert!(table.current() == borrowing.current());
+
+    println!("symbols in generation {}: {}", GENERATIONS, borrowing.current().len());
+    println!("allocations with `Vec` successors: {}", with_vec);
+    println!("allocations with `Successor`:      {}", with_successor);
+    println!("allocations with `Rules`:          {}", with_rules);
+}
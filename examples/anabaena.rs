@@ -1,22 +1,11 @@
+#[macro_use]
 extern crate lsystem;
 
 use lsystem::LSystem;
 
 use self::Anabaena::{Ar, Al, Br, Bl};
 
-macro_rules! derive_rulefn{
-    ($T:ty, $ruleset:ident, { $($pred:pat => $succ:expr),+ }) => (
-        fn $ruleset(input: $T) -> Vec<$T> {
-            match input {
-                $(
-                $pred => $succ,
-                )+
-            }
-        }
-    );
-}
-
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 enum Anabaena {
     Ar, Al,
     Br, Bl
@@ -24,30 +13,27 @@ enum Anabaena {
 
 impl std::fmt::Debug for Anabaena {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            &Ar => write!(f, "-->"),
-            &Al => write!(f, "<--"),
-            &Br => write!(f, "->"),
-            &Bl => write!(f, "<-")
+        match *self {
+            Ar => write!(f, "-->"),
+            Al => write!(f, "<--"),
+            Br => write!(f, "->"),
+            Bl => write!(f, "<-")
         }
     }
 }
 
-derive_rulefn!(Anabaena, anabaena_rule,
-    {
-        Ar => vec!(Al, Br),
-        Al => vec!(Bl, Ar),
-        Br => vec!(Ar),
-        Bl => vec!(Al)
-    }
-);
-
 fn main() {
-    let anabaena_lsystem = LSystem::new(vec!(Ar), anabaena_rule);
+    let anabaena_rules = rules!(
+        Ar => [Al, Br],
+        Al => [Bl, Ar],
+        Br => [Ar],
+        Bl => [Al]
+    );
+    let mut anabaena_lsystem = LSystem::from_rules(vec!(Ar), anabaena_rules);
 
-    for item in anabaena_lsystem.skip(4).next().unwrap().iter() {
+    for item in anabaena_lsystem.nth(4).unwrap().iter() {
         print!("{:?} ", item)
     }
     
-    println!("")
+    println!()
 }
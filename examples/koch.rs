@@ -1,21 +1,15 @@
 extern crate lsystem;
+#[macro_use]
+extern crate lsystem_derive;
 
 use lsystem::LSystem;
-use lsystem::turtle::{Turtle, TurtleInterpretation, draw};
+use lsystem::turtle::draw;
 
-#[derive(Clone)]
+#[derive(Clone, TurtleInterpretation)]
 enum Koch {
-    F, Plus, Minus
-}
-
-impl TurtleInterpretation for Koch {
-    fn to_turtle(&self) -> Turtle {
-        match *self {
-            Koch::F     => Turtle::Forward(10),
-            Koch::Plus  => Turtle::Left(90.0),
-            Koch::Minus => Turtle::Right(90.0)
-        }
-    }
+    #[turtle(forward = 10)] F,
+    #[turtle(left = 90.0)] Plus,
+    #[turtle(right = 90.0)] Minus
 }
 
 fn main() {
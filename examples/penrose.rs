@@ -1,4 +1,7 @@
+#[macro_use]
 extern crate lsystem;
+#[macro_use]
+extern crate lsystem_derive;
 
 // File & process manipulation.
 use std::io::prelude::*;
@@ -6,10 +9,9 @@ use std::io::BufWriter;
 use std::fs::{File, remove_file};
 use std::process::Command;
 
-use lsystem::LSystem;
 use lsystem::turtle::{Turtle, TurtleInterpretation};
 
-fn draw<T: TurtleInterpretation>(v: Vec<T>, index: usize) -> (String, String) {
+fn draw<T: TurtleInterpretation>(v: &[T], index: usize) -> (String, String) {
     let filename = format!("penrose-{}.py", index);
     let eps_name = format!("penrose-{}.eps", index);
 
@@ -18,7 +20,7 @@ fn draw<T: TurtleInterpretation>(v: Vec<T>, index: usize) -> (String, String) {
 
     // This sets up some nice drawing parameters in Python, and also implements a stack for the
     // turtle graphics (since the Python implementation does not have one).
-    file.write(b"import turtle
+    file.write_all(b"import turtle
 
 stack = []                   # A stack to handle Push/Pop commands.
 
@@ -67,54 +69,34 @@ turtle.getcanvas().postscript(file = '{}', colormode = 'color')
     (filename, eps_name)
 }
 
-#[derive(Clone)]
+// The M, N, O, P and Q symbols have no attribute, so they are not drawn.
+#[derive(Clone, TurtleInterpretation)]
 enum Penrose {
-    F, N, M, O, P, Q, LBrace, RBrace, Plus, Minus
-}
-
-impl TurtleInterpretation for Penrose {
-    fn to_turtle(&self) -> Turtle {
-        match *self {
-            Penrose::F      => Turtle::Forward(25),
-            Penrose::LBrace => Turtle::Push,
-            Penrose::RBrace => Turtle::Pop,
-            Penrose::Plus   => Turtle::Right(36.0),
-            Penrose::Minus  => Turtle::Left(36.0),
-            _               => Turtle::Dummy
-        }
-    }
+    #[turtle(forward = 25)] F,
+    N, M, O, P, Q,
+    #[turtle(push)] LBrace,
+    #[turtle(pop)] RBrace,
+    #[turtle(right = 36.0)] Plus,
+    #[turtle(left = 36.0)] Minus
 }
 
 fn main() {
-    use Penrose::{F, N, M, O, P, Q, LBrace, RBrace, Plus, Minus};
-    
-    // Axiom: [N]++[N]++[N]++[N]++[N]
-    let s = LSystem::new(vec!(LBrace, N, RBrace, Plus, Plus,
-                              LBrace, N, RBrace, Plus, Plus,
-                              LBrace, N, RBrace, Plus, Plus,
-                              LBrace, N, RBrace, Plus, Plus,
-                              LBrace, N, RBrace), |x| match x {
-        // M -> OA++PA----NA[-OA----MA]++
-        M => vec!(O, F, Plus, Plus, P, F, Minus, Minus, Minus, Minus, N, F, LBrace, Minus, O, F,
-                  Minus, Minus, Minus, Minus, M, F, RBrace, Plus, Plus),
-        // N -> +OA--PA[---MA--NA]+
-        N => vec!(Plus, O, F, Minus, Minus, P, F, LBrace, Minus, Minus, Minus, M, F, Minus, Minus,
-                  N, F, RBrace, Plus),
-        // O -> -MA++NA[+++OA++PA]-
-        O => vec!(Minus, M, F, Plus, Plus, N, F, LBrace, Plus, Plus, Plus, O, F, Plus, Plus, P, F,
-                  RBrace, Minus),
-        // P -> --OA++++MA[+PA++++NA]--NA
-        P => vec!(Minus, Minus, O, F, Plus, Plus, Plus, Plus, M, F, LBrace, Plus, P, F, Plus, Plus,
-                  Plus, Plus, N, F, RBrace, Minus, Minus, N, F),
-        // F -> Q
-        F => vec!(Q),
-        // All others are constants.
-        c => vec!(c)
-    });
-
-    for (index, i) in s.skip(1).take(7).enumerate() {
-        // "Draw" the current iteration by writing the Python script to produce a Postscript file.
-        let (filename, eps_name) = draw(i, index);
+    // The productions are written just as they are in the book, except that `F` stands in for
+    // its `A` and letters are separated by spaces.
+    let mut s = lsystem! {
+        symbols Penrose { M, N, O, P, F, Q, Plus = +, Minus = -, [LBrace, RBrace] }
+        axiom: [N] ++ [N] ++ [N] ++ [N] ++ [N];
+        M -> O F ++ P F ---- N F [- O F ---- M F] ++;
+        N -> + O F -- P F [--- M F -- N F] +;
+        O -> - M F ++ N F [+++ O F ++ P F] -;
+        P -> -- O F ++++ M F [+ P F ++++ N F] -- N F;
+        F -> Q;
+    };
+
+    for index in 0..7 {
+        // "Draw" the next iteration by writing the Python script to produce a Postscript file.
+        // The M, N, O and P markers only steer the derivation, and write nothing when drawn.
+        let (filename, eps_name) = draw(s.step(), index);
         let png_name = format!("penrose-{}.png", index);
 
         // Run the actual script through Python, creating a Postscript file.
@@ -123,9 +105,8 @@ fn main() {
             Err(e) => panic!("failed to execute python: {}", e),
         };
 
-        match python.wait() {
-            Err(e) => panic!("python failed with status {:?}", e),
-            Ok(_)  => ()
+        if let Err(e) = python.wait() {
+            panic!("python failed with status {:?}", e)
         }
 
         // Run the Postscript file through ImageMagick to create a `.png`.
@@ -136,9 +117,8 @@ fn main() {
             Err(e) => panic!("failed to execute convert: {}", e),
         };
 
-        match convert.wait() {
-            Err(e) => panic!("convert failed with status {:?}", e),
-            Ok(_)  => ()
+        if let Err(e) = convert.wait() {
+            panic!("convert failed with status {:?}", e)
         }
 
         // Delete the intermediate files.
@@ -154,8 +134,7 @@ fn main() {
         Err(e) => panic!("failed to execute convert: {}", e),
     };
 
-    match convert.wait() {
-        Err(e) => panic!("convert failed with status {:?}", e),
-        Ok(_)  => ()
+    if let Err(e) = convert.wait() {
+        panic!("convert failed with status {:?}", e)
     }
 }
@@ -1,4 +1,6 @@
 extern crate lsystem;
+#[macro_use]
+extern crate lsystem_derive;
 
 use lsystem::LSystem;
 use lsystem::turtle::{Turtle, TurtleInterpretation};
@@ -46,21 +48,13 @@ fn draw<T: TurtleInterpretation>(v: Vec<T>, animate: bool) {
     println!("\nturtle.exitonclick()\n");
 }
 
-#[derive(Clone)]
+#[derive(Clone, TurtleInterpretation)]
 enum Seaweed {
-    F, LBrace, RBrace, Plus, Minus
-}
-
-impl TurtleInterpretation for Seaweed {
-    fn to_turtle(&self) -> Turtle {
-        match *self {
-            Seaweed::F      => Turtle::Forward(10),
-            Seaweed::LBrace => Turtle::Push,
-            Seaweed::RBrace => Turtle::Pop,
-            Seaweed::Plus   => Turtle::Right(22.5),
-            Seaweed::Minus  => Turtle::Left(22.5),
-        }
-    }
+    #[turtle(forward = 10)] F,
+    #[turtle(push)] LBrace,
+    #[turtle(pop)] RBrace,
+    #[turtle(right = 22.5)] Plus,
+    #[turtle(left = 22.5)] Minus
 }
 
 fn main() {
@@ -1,4 +1,6 @@
 extern crate lsystem;
+#[macro_use]
+extern crate lsystem_derive;
 
 use lsystem::LSystem;
 use lsystem::turtle::{Turtle, TurtleInterpretation};
@@ -24,20 +26,12 @@ fn draw<T: TurtleInterpretation>(v: Vec<T>) {
     println!("\nturtle.exitonclick()\n");
 }
 
-#[derive(Clone)]
+#[derive(Clone, TurtleInterpretation)]
 enum Sierpinski {
-    A, B, Plus, Minus
-}
-
-impl TurtleInterpretation for Sierpinski {
-    fn to_turtle(&self) -> Turtle {
-        match *self {
-            Sierpinski::A     => Turtle::Forward(10),
-            Sierpinski::B     => Turtle::Forward(10),
-            Sierpinski::Plus  => Turtle::Left(60.0),
-            Sierpinski::Minus => Turtle::Right(60.0)
-        }
-    }
+    #[turtle(forward = 10)] A,
+    #[turtle(forward = 10)] B,
+    #[turtle(left = 60.0)] Plus,
+    #[turtle(right = 60.0)] Minus
 }
 
 fn main() {
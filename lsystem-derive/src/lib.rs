@@ -0,0 +1,185 @@
+//! Derive macros for the `lsystem` crate.
+//!
+//! Drawing an L-system over an enum alphabet usually means implementing `TurtleInterpretation`
+//! by hand, as a `match` from each variant to a `Turtle` command. This crate derives that
+//! implementation from attributes on the variants instead:
+//!
+//! * `#[turtle(forward = 10)]` moves forward the given (whole) distance;
+//! * `#[turtle(left = 36.0)]` and `#[turtle(right = 36.0)]` turn by the given (finite) angle;
+//! * `#[turtle(push)]` and `#[turtle(pop)]` save and restore the turtle's state; and
+//! * `#[turtle(dummy)]`, or no attribute at all, does nothing.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! extern crate lsystem;
+//! #[macro_use]
+//! extern crate lsystem_derive;
+//!
+//! use lsystem::turtle::{Turtle, TurtleInterpretation};
+//!
+//! #[derive(Clone, TurtleInterpretation)]
+//! enum Plant {
+//!     #[turtle(forward = 10)] F,
+//!     #[turtle(left = 25.7)] Plus,
+//!     #[turtle(right = 25.7)] Minus,
+//!     #[turtle(push)] Push,
+//!     #[turtle(pop)] Pop,
+//!     // Only steers the derivation, so it is not drawn.
+//!     X
+//! }
+//!
+//! # fn main() {
+//! match Plant::F.to_turtle() {
+//!     Turtle::Forward(distance) => assert_eq!(distance, 10),
+//!     _ => panic!("F should move forward")
+//! }
+//! match Plant::X.to_turtle() {
+//!     Turtle::Dummy => (),
+//!     _ => panic!("X should not be drawn")
+//! }
+//! # }
+//! ```
+//!
+//! Each variant can have at most one command:
+//!
+//! ```compile_fail
+//! extern crate lsystem;
+//! #[macro_use]
+//! extern crate lsystem_derive;
+//!
+//! #[derive(TurtleInterpretation)]
+//! enum Broken {
+//!     #[turtle(forward = 10, push)] F
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! And only enums can be derived, not structs or unions:
+//!
+//! ```compile_fail
+//! extern crate lsystem;
+//! #[macro_use]
+//! extern crate lsystem_derive;
+//!
+//! #[derive(TurtleInterpretation)]
+//! struct Segment {
+//!     length: i32
+//! }
+//! # fn main() {}
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::meta::ParseNestedMeta;
+use syn::{Data, DeriveInput, Error, Lit, Result, Variant};
+
+/// Derive `lsystem::turtle::TurtleInterpretation` for an enum, from the `#[turtle(...)]`
+/// attributes on its variants. See the crate documentation for details.
+#[proc_macro_derive(TurtleInterpretation, attributes(turtle))]
+pub fn derive_turtle_interpretation(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let variants = match input.data {
+        Data::Enum(ref data) => &data.variants,
+        _ => {
+            return Err(Error::new_spanned(&input.ident,
+                                          "TurtleInterpretation can only be derived for enums"))
+        }
+    };
+
+    let name = &input.ident;
+    let arms = variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let command = command(variant)?;
+        Ok(quote!(#name::#ident { .. } => #command))
+    }).collect::<Result<Vec<_>>>()?;
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lsystem::turtle::TurtleInterpretation for #name #ty_generics
+            #where_clause {
+            fn to_turtle(&self) -> ::lsystem::turtle::Turtle {
+                match *self {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+/// The `Turtle` command given by a variant's `#[turtle(...)]` attribute, or `Turtle::Dummy` if
+/// it has none.
+fn command(variant: &Variant) -> Result<TokenStream2> {
+    let mut command = None;
+
+    for attr in variant.attrs.iter().filter(|attr| attr.path().is_ident("turtle")) {
+        attr.parse_nested_meta(|meta| {
+            if command.is_some() {
+                return Err(meta.error("a variant can only have one turtle command"))
+            }
+
+            let turtle = quote!(::lsystem::turtle::Turtle);
+            command = Some(if meta.path.is_ident("forward") {
+                let distance = distance(&meta)?;
+                quote!(#turtle::Forward(#distance))
+            } else if meta.path.is_ident("left") {
+                let angle = angle(&meta)?;
+                quote!(#turtle::Left(#angle))
+            } else if meta.path.is_ident("right") {
+                let angle = angle(&meta)?;
+                quote!(#turtle::Right(#angle))
+            } else if meta.path.is_ident("push") {
+                quote!(#turtle::Push)
+            } else if meta.path.is_ident("pop") {
+                quote!(#turtle::Pop)
+            } else if meta.path.is_ident("dummy") {
+                quote!(#turtle::Dummy)
+            } else {
+                return Err(meta.error("expected one of `forward`, `left`, `right`, `push`, `pop` \
+                                       or `dummy`"))
+            });
+            Ok(())
+        })?;
+    }
+
+    Ok(command.unwrap_or_else(|| quote!(::lsystem::turtle::Turtle::Dummy)))
+}
+
+/// Parse the distance in `forward = 10`.
+fn distance(meta: &ParseNestedMeta) -> Result<u32> {
+    match meta.value()?.parse()? {
+        Lit::Int(distance) => distance.base10_parse(),
+        lit => Err(Error::new_spanned(lit, "expected a whole distance, such as `10`"))
+    }
+}
+
+/// Parse the angle in `left = 36.0` or `right = -36.0`, which may be written as an integer.
+fn angle(meta: &ParseNestedMeta) -> Result<f32> {
+    let input = meta.value()?;
+    let negative = input.parse::<Option<syn::Token![-]>>()?.is_some();
+    let lit: Lit = input.parse()?;
+    let angle: f32 = match lit {
+        Lit::Float(ref angle) => angle.base10_parse()?,
+        Lit::Int(ref angle) => angle.base10_parse()?,
+        lit => return Err(Error::new_spanned(lit, "expected an angle, such as `36.0`"))
+    };
+
+    // Too large a literal parses as an infinity, which cannot be written back out as a literal.
+    if !angle.is_finite() {
+        return Err(Error::new_spanned(lit, "angle must be a finite f32"))
+    }
+    Ok(if negative { -angle } else { angle })
+}
@@ -0,0 +1,449 @@
+//! A compressed representation of a generation as a straight-line grammar.
+//!
+//! Each generation of a deterministic, context-free L-system is built from the expansions of the
+//! symbols of its axiom, and the expansion of a symbol to depth `d` is built from the expansions
+//! of its successor to depth `d - 1`. Sharing these expansions rather than copying them turns the
+//! generation into a directed acyclic graph with at most one node per `(symbol, depth)` pair,
+//! which is a straight-line grammar (or straight-line program) for the string. The graph is tiny
+//! even when the string has billions of symbols, yet it still supports the operations usually
+//! needed of a generation: its length, iteration, random access, slicing and comparison.
+//!
+//! Nodes are reference counted, so cloning a generation is cheap, and generations built by the
+//! same `memo::Memo` share their nodes with one another.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//!
+//! let mut koch = LSystem::new(vec!('F'), |x| match x {
+//!     'F' => "F+F-F-F+F".chars().collect(),
+//!     c   => vec!(c)
+//! });
+//!
+//! // The twentieth generation has more than 190 trillion symbols, but only 62 nodes.
+//! let huge = koch.compressed(20);
+//! assert_eq!(huge.len(), 190734863281249);
+//! assert_eq!(huge.nodes(), 62);
+//! assert_eq!(huge.get(123456789012), Some(&'F'));
+//! assert_eq!(huge.slice(1000..1006).collect::<String>(), "F+F-F-");
+//!
+//! // Generations are compared node by node, rather than symbol by symbol.
+//! assert!(huge == koch.compressed(20));
+//! assert!(huge != koch.compressed(19));
+//!
+//! // Grammars that divide the same string differently are compared by lining their nodes up,
+//! // so these trillion-symbol strings are still compared without expanding either of them.
+//! let mut double = LSystem::new(vec!('F'), |x| vec!(x, x));
+//! let mut quadruple = LSystem::new(vec!('F'), |x| vec!(x, x, x, x));
+//! assert!(double.compressed(40) == quadruple.compressed(20));
+//! assert!(double.compressed(40) != quadruple.compressed(19));
+//!
+//! // Small generations can still be compared with the flat representation.
+//! let small = koch.compressed(3);
+//! assert_eq!(small.to_vec(), koch.nth(3).unwrap());
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::ptr;
+use std::sync::Arc;
+
+use Rewrite;
+
+/// The modulus of the fingerprints, which is the Mersenne prime 2^61 - 1.
+const MODULUS: u64 = (1 << 61) - 1;
+
+/// The base of the fingerprints, which are polynomials in it.
+const BASE: u64 = 0x0123_4567_89AB_CDEF % MODULUS;
+
+/// A node of the straight-line grammar.
+struct Node<T> {
+    /// The number of symbols the node expands to.
+    length: u64,
+    /// A polynomial hash of the symbols the node expands to, so that equal strings have equal
+    /// fingerprints however they are divided into nodes.
+    fingerprint: u64,
+    /// `BASE` raised to the power of `length`, for combining fingerprints.
+    power: u64,
+    kind: Kind<T>
+}
+
+enum Kind<T> {
+    /// A single symbol.
+    Symbol(T),
+    /// The concatenation of other nodes.
+    Concat(Vec<Arc<Node<T>>>)
+}
+
+impl<T> Node<T> {
+    fn symbol(symbol: T) -> Arc<Node<T>> where T: Hash {
+        let mut hasher = DefaultHasher::new();
+        symbol.hash(&mut hasher);
+        let fingerprint = hasher.finish() % (MODULUS - 1) + 1;
+        Arc::new(Node { length: 1, fingerprint, power: BASE, kind: Kind::Symbol(symbol) })
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the length of the concatenation does not fit in a `u64`.
+    fn concat(children: Vec<Arc<Node<T>>>) -> Arc<Node<T>> {
+        let (mut length, mut fingerprint, mut power) = (0u64, 0, 1);
+        for child in children.iter() {
+            length = match length.checked_add(child.length) {
+                Some(length) => length,
+                None => panic!("compressed generation is too long")
+            };
+            fingerprint = (multiply(fingerprint, child.power) + child.fingerprint) % MODULUS;
+            power = multiply(power, child.power);
+        }
+        Arc::new(Node { length, fingerprint, power, kind: Kind::Concat(children) })
+    }
+}
+
+fn multiply(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % MODULUS as u128) as u64
+}
+
+/// A generation of an L-system stored as a straight-line grammar, so that repeated substrings are
+/// only stored once.
+///
+/// This is usually created with `LSystem::compressed`.
+///
+/// Two generations are equal when they contain the same symbols. Each node of the grammar carries
+/// a fingerprint of the symbols it expands to, so most unequal generations are told apart at
+/// once, and equal generations whose grammars have the same shape are compared one node at a time,
+/// comparing each pair of nodes only once. Only where their shapes differ are the symbols
+/// themselves compared.
+pub struct CompressedGeneration<T> {
+    root: Arc<Node<T>>
+}
+
+impl<T> CompressedGeneration<T> where T: Clone + Eq + Hash {
+    /// Build the generation that results from applying the production rules `n` times to the
+    /// axiom, without ever holding it as a flat string.
+    ///
+    /// The rules should be deterministic, since each symbol is only ever rewritten once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the generation does not fit in a `u64`.
+    pub fn new<R>(axiom: &[T], rules: &mut R, n: usize) -> CompressedGeneration<T>
+        where R: Rewrite<T> {
+        Expansions::new().generation(rules, axiom, n)
+    }
+}
+
+impl<T> CompressedGeneration<T> {
+    /// The number of symbols in the generation.
+    pub fn len(&self) -> u64 {
+        self.root.length
+    }
+
+    /// Whether the generation contains no symbols at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of distinct nodes in the grammar, which is a measure of the memory it uses.
+    pub fn nodes(&self) -> usize {
+        let mut seen = HashSet::new();
+        let mut pending = vec!(&*self.root);
+
+        while let Some(node) = pending.pop() {
+            if seen.insert(node as *const Node<T>) {
+                if let Kind::Concat(ref children) = node.kind {
+                    pending.extend(children.iter().map(|child| &**child));
+                }
+            }
+        }
+        seen.len()
+    }
+
+    /// The symbol at position `k`, if the generation is that long.
+    pub fn get(&self, k: u64) -> Option<&T> {
+        self.slice(k..k.saturating_add(1)).next()
+    }
+
+    /// Iterate over every symbol of the generation.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.slice(0..self.len())
+    }
+
+    /// Iterate over the symbols in the given range of positions. Positions beyond the end of the
+    /// generation are ignored.
+    pub fn slice(&self, range: Range<u64>) -> Iter<'_, T> {
+        Iter::new(&self.root, range)
+    }
+
+    /// Flatten the generation into a vector of symbols.
+    pub fn to_vec(&self) -> Vec<T> where T: Clone {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Clone for CompressedGeneration<T> {
+    fn clone(&self) -> CompressedGeneration<T> {
+        CompressedGeneration { root: Arc::clone(&self.root) }
+    }
+}
+
+impl<T> PartialEq for CompressedGeneration<T> where T: PartialEq {
+    fn eq(&self, other: &CompressedGeneration<T>) -> bool {
+        Equality { known: HashSet::new() }.nodes(&self.root, &other.root)
+    }
+}
+
+impl<T> Eq for CompressedGeneration<T> where T: Eq {}
+
+/// A comparison of two grammars, which remembers the pairs of nodes already found to be equal.
+struct Equality<T> {
+    /// Triples `(a, b, offset)` for which `a` is known to match `b` from position `offset`.
+    known: HashSet<(*const Node<T>, *const Node<T>, u64)>
+}
+
+impl<T> Equality<T> where T: PartialEq {
+    fn nodes(&mut self, a: &Node<T>, b: &Node<T>) -> bool {
+        if ptr::eq(a, b) {
+            return true
+        }
+
+        // Equal strings always have equal fingerprints, so different fingerprints decide the
+        // comparison. Equal fingerprints only make equality likely, so the nodes are still
+        // compared below.
+        if a.length != b.length || a.fingerprint != b.fingerprint {
+            return false
+        }
+
+        // A concatenation of a single node is the same string as that node.
+        if let Kind::Concat(ref children) = a.kind {
+            if children.len() == 1 {
+                return self.nodes(&children[0], b)
+            }
+        }
+        if let Kind::Concat(ref children) = b.kind {
+            if children.len() == 1 {
+                return self.nodes(a, &children[0])
+            }
+        }
+
+        let key = (a as *const Node<T>, b as *const Node<T>, 0);
+        if self.known.contains(&key) {
+            return true
+        }
+
+        let equal = match (&a.kind, &b.kind) {
+            (Kind::Symbol(x), Kind::Symbol(y)) => x == y,
+            (Kind::Concat(xs), Kind::Concat(ys))
+                if xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| x.length == y.length) => {
+                xs.iter().zip(ys).all(|(x, y)| self.nodes(x, y))
+            },
+            // The nodes divide their strings differently, so line the children of one up against
+            // the other.
+            (Kind::Concat(_), _) => self.children(a, b, 0),
+            (_, Kind::Concat(_)) => self.children(b, a, 0)
+        };
+
+        if equal {
+            self.known.insert(key);
+        }
+        equal
+    }
+
+    /// Whether `a` expands to the same string as `b` does from position `offset`, for as many
+    /// symbols as `a` has.
+    fn range(&mut self, a: &Node<T>, b: &Node<T>, offset: u64) -> bool {
+        if offset == 0 && a.length == b.length {
+            return self.nodes(a, b)
+        }
+        if a.length == 0 {
+            return true
+        }
+
+        let key = (a as *const Node<T>, b as *const Node<T>, offset);
+        if self.known.contains(&key) {
+            return true
+        }
+
+        // If the range lies within a single child of `b`, compare against that child alone.
+        // Otherwise the range spans several children, and so `a` is split into its own.
+        let end = offset + a.length;
+        let mut within = None;
+        if let Kind::Concat(ref children) = b.kind {
+            let mut start = 0;
+            for child in children.iter() {
+                if offset >= start && end <= start + child.length {
+                    within = Some((child, start));
+                    break
+                }
+                start += child.length;
+            }
+        }
+
+        let equal = match within {
+            Some((child, start)) => self.range(a, child, offset - start),
+            None => self.children(a, b, offset)
+        };
+
+        if equal {
+            self.known.insert(key);
+        }
+        equal
+    }
+
+    /// Whether each child of `a` matches the part of `b` that it lines up with, when `a` is
+    /// placed at position `offset` of `b`.
+    fn children(&mut self, a: &Node<T>, b: &Node<T>, offset: u64) -> bool {
+        match a.kind {
+            Kind::Concat(ref children) => {
+                let mut position = offset;
+                children.iter().all(|child| {
+                    let equal = self.range(child, b, position);
+                    position += child.length;
+                    equal
+                })
+            },
+            // A single symbol always lies within a single child, so it is never split.
+            Kind::Symbol(_) => unreachable!()
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CompressedGeneration<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator over the symbols of a `CompressedGeneration`, which walks the grammar depth-first.
+pub struct Iter<'a, T: 'a> {
+    stack: Vec<(&'a Node<T>, usize)>,
+    remaining: u64
+}
+
+impl<'a, T> Iter<'a, T> {
+    /// Iterate over the symbols that `root` expands to in the given range of positions.
+    fn new(root: &'a Node<T>, range: Range<u64>) -> Iter<'a, T> {
+        let end = range.end.min(root.length);
+        let mut offset = range.start;
+        let mut stack = Vec::new();
+
+        if offset < end {
+            // Descend to the symbol at the start of the range, leaving behind a frame for each
+            // concatenation that records which of its children should be visited next.
+            let mut node = root;
+            while let Kind::Concat(ref children) = node.kind {
+                let mut index = 0;
+                while offset >= children[index].length {
+                    offset -= children[index].length;
+                    index += 1;
+                }
+                stack.push((node, index + 1));
+                node = &children[index];
+            }
+            stack.push((node, 0));
+        }
+
+        Iter { stack, remaining: end.saturating_sub(range.start) }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None
+        }
+
+        while let Some((node, next)) = self.stack.pop() {
+            match node.kind {
+                Kind::Symbol(ref symbol) => {
+                    self.remaining -= 1;
+                    return Some(symbol)
+                },
+                Kind::Concat(ref children) => {
+                    if next < children.len() {
+                        self.stack.push((node, next + 1));
+                        self.stack.push((&children[next], 0));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.remaining > usize::MAX as u64 {
+            (usize::MAX, None)
+        } else {
+            (self.remaining as usize, Some(self.remaining as usize))
+        }
+    }
+}
+
+/// The successor of every symbol rewritten so far, and the node for every expansion built so far,
+/// from which `CompressedGeneration`s are assembled.
+pub(crate) struct Expansions<T> {
+    successors: HashMap<T, Vec<T>>,
+    expansions: HashMap<(T, usize), Arc<Node<T>>>
+}
+
+impl<T> Expansions<T> where T: Clone + Eq + Hash {
+    pub(crate) fn new() -> Expansions<T> {
+        Expansions { successors: HashMap::new(), expansions: HashMap::new() }
+    }
+
+    /// The generation that results from applying the rules `depth` times to `axiom`.
+    pub(crate) fn generation<R>(&mut self, rules: &mut R, axiom: &[T], depth: usize)
+                                -> CompressedGeneration<T> where R: Rewrite<T> {
+        let children = axiom.iter().map(|symbol| self.node(rules, symbol, depth)).collect();
+        CompressedGeneration { root: Node::concat(children) }
+    }
+
+    /// The string that a single symbol expands to after `depth` steps.
+    pub(crate) fn expansion<R>(&mut self, rules: &mut R, symbol: &T, depth: usize)
+                               -> CompressedGeneration<T> where R: Rewrite<T> {
+        CompressedGeneration { root: self.node(rules, symbol, depth) }
+    }
+
+    /// The number of expansions built so far.
+    pub(crate) fn len(&self) -> usize {
+        self.expansions.len()
+    }
+
+    /// Forget every expansion built so far, but not the successors.
+    pub(crate) fn clear(&mut self) {
+        self.expansions.clear();
+    }
+
+    /// The node for the expansion of a single symbol to the given depth, creating it (and the
+    /// nodes it depends on) if it does not yet exist.
+    fn node<R>(&mut self, rules: &mut R, symbol: &T, depth: usize) -> Arc<Node<T>>
+        where R: Rewrite<T> {
+        let key = (symbol.clone(), depth);
+        if let Some(node) = self.expansions.get(&key) {
+            return Arc::clone(node)
+        }
+
+        let node = if depth == 0 {
+            Node::symbol(symbol.clone())
+        } else {
+            if !self.successors.contains_key(symbol) {
+                let successor = rules.rewrite(symbol.clone()).into_iter().collect();
+                self.successors.insert(symbol.clone(), successor);
+            }
+            let successor = self.successors[symbol].clone();
+            let children = successor.iter().map(|s| self.node(rules, s, depth - 1)).collect();
+            Node::concat(children)
+        };
+
+        self.expansions.insert(key, Arc::clone(&node));
+        node
+    }
+}
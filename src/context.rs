@@ -0,0 +1,307 @@
+//! Context-sensitive L-systems, in which a production can inspect the neighbours of the symbol it
+//! is rewriting.
+//!
+//! In the context-free `LSystem`, every symbol is rewritten in isolation. This is enough for many
+//! growth patterns, but it cannot express the flow of information through a structure, such as
+//! the acropetal signals described in section 1.8 of _The Algorithmic Beauty of Plants_. The
+//! `ContextLSystem` type lifts this restriction by handing each production a `Context`, which
+//! exposes the symbol being rewritten as well as its left and right neighbours in the previous
+//! generation. Both 1L-systems (which only look to one side) and 2L-systems (which look to both)
+//! can be written this way; a 1L-system simply ignores one of the neighbours.
+//!
+//! ## An Example
+//!
+//! A signal `B` travelling along a filament of `A` cells can be modelled with the productions
+//! `B < A -> B` and `B -> A`: a cell becomes the signal when its left neighbour carries it, and
+//! the signal moves on once it has been passed along.
+//!
+//! ```rust
+//! use lsystem::context::ContextLSystem;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Cell { A, B }
+//!
+//! let mut signal = ContextLSystem::new(vec!(Cell::B, Cell::A, Cell::A), |ctx| {
+//!     match (ctx.left(), ctx.symbol()) {
+//!         (Some(&Cell::B), &Cell::A) => vec!(Cell::B),
+//!         (_, &Cell::B)              => vec!(Cell::A),
+//!         (_, c)                     => vec!(c.clone())
+//!     }
+//! });
+//!
+//! assert_eq!(signal.nth(2).unwrap(), vec!(Cell::A, Cell::A, Cell::B));
+//! ```
+//!
+//! ## Branching Structures
+//!
+//! In bracketed L-systems the string is a flattened representation of an axial tree, so the
+//! textual neighbours of a symbol are not necessarily its neighbours in the plant. Giving a
+//! `ContextLSystem` a set of `Brackets` makes its contexts follow the tree instead: the left
+//! context of a symbol becomes its parent segment, the right context becomes the continuation of
+//! its axis, and the symbols that start each child branch are available through
+//! `Context::rights`. Symbols that carry no structural meaning, such as turning commands, can be
+//! ignored entirely during the search.
+//!
+//! ```rust
+//! use lsystem::context::{Brackets, ContextLSystem};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Plant { A, B, Plus, LBrace, RBrace }
+//!
+//! use Plant::{A, B, Plus, LBrace, RBrace};
+//!
+//! # fn main() {
+//! // Axiom: B[+A]A
+//! let mut signal = ContextLSystem::new(vec!(B, LBrace, Plus, A, RBrace, A), |ctx| {
+//!     match (ctx.left(), ctx.symbol()) {
+//!         (Some(&B), &A) => vec!(B),
+//!         (_, &B)        => vec!(A),
+//!         (_, c)         => vec!(c.clone())
+//!     }
+//! }).with_brackets(Brackets::new(LBrace, RBrace).ignoring(vec!(Plus)));
+//!
+//! // The signal enters both the branch and the main axis: A[+B]B
+//! assert_eq!(signal.nth(1).unwrap(), vec!(A, LBrace, Plus, B, RBrace, B));
+//! # }
+//! ```
+
+use std::mem;
+
+/// The symbols that open and close branches in a bracketed L-system, along with any symbols that
+/// should be skipped over when searching for context.
+pub struct Brackets<T> {
+    push: T,
+    pop: T,
+    ignored: Vec<T>
+}
+
+impl<T> Brackets<T> where T: PartialEq {
+    /// Creates a new set of brackets from the symbols that start (`push`) and end (`pop`) a
+    /// branch.
+    pub fn new(push: T, pop: T) -> Brackets<T> {
+        Brackets { push, pop, ignored: Vec::new() }
+    }
+
+    /// Adds symbols which should be invisible to context matching, such as turning commands.
+    pub fn ignoring(mut self, symbols: Vec<T>) -> Brackets<T> {
+        self.ignored.extend(symbols);
+        self
+    }
+
+    /// Whether the given symbol starts a branch.
+    pub fn is_push(&self, symbol: &T) -> bool {
+        *symbol == self.push
+    }
+
+    /// Whether the given symbol ends a branch.
+    pub fn is_pop(&self, symbol: &T) -> bool {
+        *symbol == self.pop
+    }
+
+    /// Whether the given symbol is ignored by context matching.
+    pub fn is_ignored(&self, symbol: &T) -> bool {
+        self.ignored.contains(symbol)
+    }
+
+    /// Find the index of the matching bracket for every bracket in the string, so that whole
+    /// branches can be skipped in constant time. Unbalanced brackets have no match.
+    fn pairs(&self, string: &[T]) -> Vec<Option<usize>> {
+        let mut pairs = vec!(None; string.len());
+        let mut open = Vec::new();
+
+        for (index, symbol) in string.iter().enumerate() {
+            if self.is_push(symbol) {
+                open.push(index);
+            } else if self.is_pop(symbol) {
+                if let Some(start) = open.pop() {
+                    pairs[start] = Some(index);
+                    pairs[index] = Some(start);
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// The neighbourhood of a symbol that is about to be rewritten by a context-sensitive production.
+///
+/// All of the symbols exposed by a `Context` come from the previous generation, so the result of
+/// rewriting one symbol never influences the context seen by another within the same step.
+///
+/// When the L-system has `Brackets`, the neighbours are found by walking the axial tree encoded by
+/// the string rather than by looking at adjacent symbols.
+pub struct Context<'a, T: 'a> {
+    string: &'a [T],
+    index: usize,
+    brackets: Option<&'a Brackets<T>>,
+    pairs: &'a [Option<usize>]
+}
+
+impl<'a, T> Context<'a, T> where T: PartialEq {
+    /// The symbol being rewritten.
+    pub fn symbol(&self) -> &'a T {
+        &self.string[self.index]
+    }
+
+    /// The symbol to the left of the one being rewritten, if there is one.
+    ///
+    /// In a bracketed system this is the closest ancestor on the axial tree: sibling branches
+    /// that lie between the two are skipped, as are ignored symbols.
+    pub fn left(&self) -> Option<&'a T> {
+        let brackets = match self.brackets {
+            Some(brackets) => brackets,
+            None => return if self.index == 0 { None } else { self.string.get(self.index - 1) }
+        };
+
+        let mut index = self.index;
+        while index > 0 {
+            index -= 1;
+            let symbol = &self.string[index];
+
+            if brackets.is_pop(symbol) {
+                // Jump to the start of the sibling branch; the loop then steps past it.
+                match self.pairs[index] {
+                    Some(start) => index = start,
+                    None => return None
+                }
+            } else if !brackets.is_push(symbol) && !brackets.is_ignored(symbol) {
+                return Some(symbol)
+            }
+        }
+        None
+    }
+
+    /// The symbol to the right of the one being rewritten, if there is one.
+    ///
+    /// In a bracketed system this is the next symbol on the same axis: child branches are
+    /// skipped, as are ignored symbols, and the end of the enclosing branch has no right context.
+    pub fn right(&self) -> Option<&'a T> {
+        let brackets = match self.brackets {
+            Some(brackets) => brackets,
+            None => return self.string.get(self.index + 1)
+        };
+
+        let mut index = self.index + 1;
+        while index < self.string.len() {
+            let symbol = &self.string[index];
+
+            if brackets.is_push(symbol) {
+                match self.pairs[index] {
+                    Some(end) => index = end + 1,
+                    None => return None
+                }
+            } else if brackets.is_pop(symbol) {
+                return None
+            } else if brackets.is_ignored(symbol) {
+                index += 1;
+            } else {
+                return Some(symbol)
+            }
+        }
+        None
+    }
+
+    /// Every symbol that could be considered the right context of the one being rewritten.
+    ///
+    /// In a bracketed system this contains the first symbol of each child branch, in order,
+    /// followed by the continuation of the current axis. Otherwise it contains at most the
+    /// adjacent symbol.
+    pub fn rights(&self) -> Vec<&'a T> {
+        let mut found = Vec::new();
+
+        match self.brackets {
+            Some(brackets) => self.successors(brackets, self.index + 1, &mut found),
+            None => found.extend(self.string.get(self.index + 1))
+        }
+        found
+    }
+
+    /// Collect the symbols that can follow on from position `start` in the axial tree.
+    fn successors(&self, brackets: &Brackets<T>, start: usize, found: &mut Vec<&'a T>) {
+        let mut index = start;
+        while index < self.string.len() {
+            let symbol = &self.string[index];
+
+            if brackets.is_push(symbol) {
+                self.successors(brackets, index + 1, found);
+                match self.pairs[index] {
+                    Some(end) => index = end + 1,
+                    None => return
+                }
+            } else if brackets.is_pop(symbol) {
+                return
+            } else if brackets.is_ignored(symbol) {
+                index += 1;
+            } else {
+                found.push(symbol);
+                return
+            }
+        }
+    }
+
+    /// The position of the symbol being rewritten in the previous generation.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Create a context-sensitive Lindenmayer System defined by an axiom of type `Vec<T>` and a rule
+/// function (or closure) which maps the `Context` of each symbol to a vector of values of type
+/// `T`.
+///
+/// This type behaves exactly like `LSystem`: the first item yielded by the iterator is the axiom
+/// itself, and every subsequent item is the next generation. The iterator is never exhausted.
+pub struct ContextLSystem<T, F> where F: FnMut(Context<T>) -> Vec<T> {
+    axiom: Vec<T>,
+    rules: F,
+    brackets: Option<Brackets<T>>,
+    zeroth: bool
+}
+
+impl<T, F> ContextLSystem<T, F> where F: FnMut(Context<T>) -> Vec<T> {
+    /// Creates a new representation of a context-sensitive L-system with the given axiom and
+    /// production rules.
+    pub fn new(axiom: Vec<T>, rules: F) -> ContextLSystem<T, F> {
+        ContextLSystem { axiom, rules, brackets: None, zeroth: true }
+    }
+
+    /// Match contexts on the axial tree described by the given brackets, rather than on the flat
+    /// string.
+    pub fn with_brackets(mut self, brackets: Brackets<T>) -> ContextLSystem<T, F> {
+        self.brackets = Some(brackets);
+        self
+    }
+}
+
+impl<T, F> Iterator for ContextLSystem<T, F> where T: Clone + PartialEq,
+                                                   F: FnMut(Context<T>) -> Vec<T> {
+    type Item = Vec<T>;
+
+    /// Yield the next iteration of the L-system by rewriting each symbol of the current axiom in
+    /// the context of its neighbours.
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.zeroth {
+            self.zeroth = false;
+            return Some(self.axiom.clone())
+        }
+
+        // Unlike the context-free case, the old axiom must be kept intact until every symbol has
+        // been rewritten, since it provides the context for each production.
+        let old_axiom = mem::take(&mut self.axiom);
+        let pairs = match self.brackets {
+            Some(ref brackets) => brackets.pairs(&old_axiom),
+            None => Vec::new()
+        };
+
+        for index in 0..old_axiom.len() {
+            let context = Context {
+                string: &old_axiom,
+                index,
+                brackets: self.brackets.as_ref(),
+                pairs: &pairs
+            };
+            self.axiom.extend((self.rules)(context));
+        }
+        Some(self.axiom.clone())
+    }
+}
@@ -0,0 +1,162 @@
+//! Detecting when a derivation reaches a fixed point or starts to repeat itself.
+//!
+//! Many L-systems stop changing after a few generations, once every symbol has been rewritten
+//! into a constant, and others settle into a cycle of generations that repeats forever. Since the
+//! rules of an L-system (stochastic rules aside) are applied in the same way every time, as soon
+//! as one generation equals an earlier one, every generation after it is already known. The
+//! `Cycles` adapter stops iterating at that point, and reports where the cycle starts and how
+//! long it is.
+//!
+//! Generations can be compared by equality, which requires keeping a copy of each of them, or by
+//! hash, which only keeps a 64-bit hash of each generation. Hash collisions are very unlikely,
+//! but not impossible, so the latter is best suited to long derivations of large generations.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//! use lsystem::cycle::{Cycle, DetectCycles};
+//!
+//! // Everything eventually becomes a C.
+//! let mut decay = LSystem::new(vec!('A', 'B'), |x| match x {
+//!     'A' => vec!('B'),
+//!     _   => vec!('C')
+//! }).until_cycle();
+//!
+//! assert_eq!(decay.by_ref().count(), 3);
+//! assert_eq!(decay.detected(), Some(Cycle { start: 2, period: 1 }));
+//! assert!(decay.detected().unwrap().is_fixed_point());
+//!
+//! // Whereas here, A and B alternate forever.
+//! let mut blinker = LSystem::new(vec!('A', 'A'), |x| match x {
+//!     'A' => vec!('B'),
+//!     _   => vec!('A')
+//! }).until_cycle_hashed();
+//!
+//! assert_eq!(blinker.by_ref().last(), Some(vec!('B', 'B')));
+//! assert_eq!(blinker.detected(), Some(Cycle { start: 0, period: 2 }));
+//! ```
+
+use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+
+/// Where a repeating sequence of generations begins, and how long it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The index of the first generation of the cycle, counting from the first generation the
+    /// adapter saw.
+    pub start: usize,
+    /// The number of generations in the cycle.
+    pub period: usize
+}
+
+impl Cycle {
+    /// Whether the cycle is a single generation that rewrites to itself.
+    pub fn is_fixed_point(&self) -> bool {
+        self.period == 1
+    }
+}
+
+/// The generations seen so far, in whichever form they are being compared.
+enum Seen<T> {
+    Generations(Vec<T>),
+    Hashes(HashMap<u64, usize>)
+}
+
+/// An iterator adapter that yields generations until one of them repeats an earlier generation.
+///
+/// This is created with the methods of `DetectCycles`.
+pub struct Cycles<I> where I: Iterator {
+    generations: I,
+    seen: Seen<I::Item>,
+    // Either `by_equality` or `by_hash`, chosen when the adapter is created so that the iterator
+    // itself does not need to require both.
+    find: fn(&mut Seen<I::Item>, &I::Item) -> Option<usize>,
+    index: usize,
+    cycle: Option<Cycle>
+}
+
+impl<I> Cycles<I> where I: Iterator {
+    /// The cycle that stopped the iteration, once it has been found.
+    pub fn detected(&self) -> Option<Cycle> {
+        self.cycle
+    }
+}
+
+impl<I> Iterator for Cycles<I> where I: Iterator {
+    type Item = I::Item;
+
+    /// Yield the next generation, unless it is the first repeat of an earlier one.
+    fn next(&mut self) -> Option<I::Item> {
+        if self.cycle.is_some() {
+            return None
+        }
+
+        let generation = self.generations.next()?;
+        if let Some(start) = (self.find)(&mut self.seen, &generation) {
+            self.cycle = Some(Cycle { start, period: self.index - start });
+            return None
+        }
+        self.index += 1;
+        Some(generation)
+    }
+}
+
+/// Adds cycle detection to any iterator over generations, such as an `LSystem`.
+pub trait DetectCycles: Iterator + Sized {
+    /// Stop once a generation equals an earlier one, keeping a copy of every generation to
+    /// compare against.
+    fn until_cycle(self) -> Cycles<Self> where Self::Item: Clone + PartialEq {
+        Cycles {
+            generations: self,
+            seen: Seen::Generations(Vec::new()),
+            find: by_equality,
+            index: 0,
+            cycle: None
+        }
+    }
+
+    /// Stop once a generation has the same hash as an earlier one, keeping only the hash of
+    /// every generation.
+    fn until_cycle_hashed(self) -> Cycles<Self> where Self::Item: Hash {
+        Cycles {
+            generations: self,
+            seen: Seen::Hashes(HashMap::new()),
+            find: by_hash,
+            index: 0,
+            cycle: None
+        }
+    }
+}
+
+impl<I> DetectCycles for I where I: Iterator {}
+
+/// Find the index of an earlier generation equal to this one, or remember it if there is none.
+fn by_equality<T>(seen: &mut Seen<T>, generation: &T) -> Option<usize> where T: Clone + PartialEq {
+    if let Seen::Generations(ref mut generations) = *seen {
+        if let Some(start) = generations.iter().position(|earlier| earlier == generation) {
+            return Some(start)
+        }
+        generations.push(generation.clone());
+    }
+    None
+}
+
+/// Find the index of an earlier generation with the same hash as this one, or remember its hash
+/// if there is none.
+fn by_hash<T>(seen: &mut Seen<T>, generation: &T) -> Option<usize> where T: Hash {
+    if let Seen::Hashes(ref mut hashes) = *seen {
+        let mut hasher = DefaultHasher::new();
+        generation.hash(&mut hasher);
+
+        let index = hashes.len();
+        match hashes.entry(hasher.finish()) {
+            Entry::Occupied(earlier) => return Some(*earlier.get()),
+            Entry::Vacant(entry) => {
+                entry.insert(index);
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,54 @@
+//! The error type for fallible derivations.
+
+use std::error;
+use std::fmt;
+
+use limits::LimitExceeded;
+
+/// The ways in which deriving a generation can fail, where `E` is the type of the errors returned
+/// by fallible production rules.
+///
+/// Every variant reports the generation that was being produced when the derivation failed, which
+/// is one more than the index of the generation being rewritten.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error<E> {
+    /// A production rule returned an error.
+    Rule {
+        /// The generation that was being produced.
+        generation: usize,
+        /// The position of the symbol that could not be rewritten, within the generation before
+        /// `generation`.
+        position: usize,
+        /// The error returned by the rule.
+        error: E
+    },
+    /// The derivation exceeded one of its limits.
+    Limit(LimitExceeded)
+}
+
+impl<E> From<LimitExceeded> for Error<E> {
+    fn from(e: LimitExceeded) -> Error<E> {
+        Error::Limit(e)
+    }
+}
+
+impl<E> fmt::Display for Error<E> where E: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Rule { generation, position, ref error } => {
+                write!(f, "failed to rewrite symbol {} while producing generation {}: {}", position,
+                       generation, error)
+            },
+            Error::Limit(ref e) => write!(f, "{}", e)
+        }
+    }
+}
+
+impl<E> error::Error for Error<E> where E: error::Error + 'static {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Rule { ref error, .. } => Some(error),
+            Error::Limit(ref e) => Some(e)
+        }
+    }
+}
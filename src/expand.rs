@@ -0,0 +1,59 @@
+//! Lazy, depth-first expansion of a generation as a stream of symbols.
+//!
+//! Producing generation `n` of an L-system by repeated rewriting requires holding the whole of
+//! generation `n - 1` in memory, and the length of a generation usually grows exponentially. But
+//! when the symbols only need to be visited once (to feed them to a turtle, or to count them),
+//! there is no need to build the string at all. Since each symbol is rewritten independently, the
+//! `n`th generation is simply the concatenation of the expansions of each symbol of the axiom to
+//! depth `n`, and these can be produced one at a time by a depth-first traversal. The `Expand`
+//! iterator does exactly this, keeping only one successor per level of the derivation, so that
+//! its memory use is proportional to `n` rather than to the length of the generation.
+
+use std::slice;
+
+use Rewrite;
+
+/// An iterator over the symbols of a generation, produced lazily by depth-first expansion.
+///
+/// This is usually created with `LSystem::expand`.
+pub struct Expand<'a, T: 'a, R: 'a> where R: Rewrite<T> {
+    root: slice::Iter<'a, T>,
+    rules: &'a mut R,
+    stack: Vec<<R::Output as IntoIterator>::IntoIter>,
+    depth: usize
+}
+
+impl<'a, T, R> Expand<'a, T, R> where R: Rewrite<T> {
+    /// Expands each symbol of the `axiom` by applying the production rules `depth` times.
+    pub fn new(axiom: &'a [T], rules: &'a mut R, depth: usize) -> Expand<'a, T, R> {
+        Expand { root: axiom.iter(), rules, stack: Vec::with_capacity(depth), depth }
+    }
+}
+
+impl<'a, T, R> Iterator for Expand<'a, T, R> where T: Clone, R: Rewrite<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            // Take the next symbol from the deepest unfinished successor, or from the axiom once
+            // every successor has been exhausted. Symbols in the successor at the top of the
+            // stack have already been rewritten once for every level below it.
+            let (symbol, remaining) = match self.stack.last_mut().map(|frame| frame.next()) {
+                Some(Some(symbol)) => (symbol, self.depth - self.stack.len()),
+                Some(None) => {
+                    self.stack.pop();
+                    continue
+                },
+                None => match self.root.next() {
+                    Some(symbol) => (symbol.clone(), self.depth),
+                    None => return None
+                }
+            };
+
+            if remaining == 0 {
+                return Some(symbol)
+            }
+            self.stack.push(self.rules.rewrite(symbol).into_iter());
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! L-systems with fallible production rules.
+//!
+//! Some rules can meet symbols they are unable to rewrite: a parametric rule may be handed a
+//! `NaN` parameter, and a grammar loaded from a file may contain symbols that were never defined.
+//! Rather than panicking, the rules of a `TryLSystem` return a `Result`. The first error stops the
+//! derivation, and is reported together with the generation being produced and the position of
+//! the offending symbol.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::error::Error;
+//! use lsystem::fallible::TryLSystem;
+//!
+//! let mut koch = TryLSystem::new("F+F?".chars().collect(), |x| match x {
+//!     'F'       => Ok("F+F-F-F+F".chars().collect()),
+//!     '+' | '-' => Ok(vec!(x)),
+//!     c         => Err(format!("unknown symbol `{}`", c))
+//! });
+//!
+//! assert_eq!(koch.next(), Some(Ok("F+F?".chars().collect())));
+//! assert_eq!(koch.next(), Some(Err(Error::Rule {
+//!     generation: 1,
+//!     position: 3,
+//!     error: "unknown symbol `?`".to_string()
+//! })));
+//!
+//! // The derivation stops at the first error, but the last good generation is kept.
+//! assert_eq!(koch.next(), None);
+//! assert_eq!(koch.current()[3], '?');
+//! ```
+
+use std::mem;
+
+use derive;
+use error::Error;
+use limits::Limits;
+
+/// Create a Lindenmayer System defined by an axiom of type `Vec<T>` and a fallible rule function
+/// (or closure) which maps values of type `T` to either an iterable collection of values of type
+/// `T` or an error of type `E`.
+///
+/// The iterator yields `Ok` for the axiom and every generation after it, until either a rule
+/// fails or one of the L-system's `Limits` is exceeded. It then yields a single `Err`, after which
+/// it is exhausted.
+///
+/// Like those of `LSystem`, the rules may return any iterable successor, not only a `Vec`:
+///
+/// ```rust
+/// use lsystem::fallible::TryLSystem;
+///
+/// // Erase every `x`, and reject digits.
+/// let mut erase = TryLSystem::new("axbxc".chars().collect(), |c: char| match c {
+///     'x'                   => Ok(None),
+///     c if c.is_numeric()   => Err(c),
+///     c                     => Ok(Some(c))
+/// });
+///
+/// assert_eq!(erase.try_step(), Ok(&['a', 'b', 'c'][..]));
+/// ```
+pub struct TryLSystem<T, F> {
+    axiom: Vec<T>,
+    rules: F,
+    limits: Limits,
+    generation: usize,
+    fresh: bool,
+    failed: bool
+}
+
+impl<T, F, I, E> TryLSystem<T, F> where F: FnMut(T) -> Result<I, E>, I: IntoIterator<Item = T> {
+    /// Creates a new representation of an L-system with the given axiom and fallible production
+    /// rules.
+    pub fn new(axiom: Vec<T>, rules: F) -> TryLSystem<T, F> {
+        TryLSystem {
+            axiom,
+            rules,
+            limits: Limits::new(),
+            generation: 0,
+            fresh: true,
+            failed: false
+        }
+    }
+
+    /// Limit the derivation of this L-system, replacing any existing limits.
+    pub fn with_limits(mut self, limits: Limits) -> TryLSystem<T, F> {
+        self.limits = limits;
+        self
+    }
+
+    /// The current generation of the L-system. This is never a partially rewritten generation:
+    /// when a step fails, the generation that was being rewritten remains current.
+    pub fn current(&self) -> &[T] {
+        &self.axiom
+    }
+
+    /// The index of the current generation, where the axiom is generation zero.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Rewrite the current generation's contents using the production rules, and return the
+    /// result by reference. If a rule fails or a limit is exceeded, the current generation is
+    /// left as it was.
+    pub fn try_step(&mut self) -> Result<&[T], Error<E>> where T: Clone {
+        let bytes = self.axiom.capacity() * mem::size_of::<T>();
+        let mut next = Vec::new();
+        derive(&mut next, self.axiom.iter().cloned(), bytes, &self.limits, self.generation + 1,
+               None, &mut self.rules)?;
+
+        self.axiom = next;
+        self.generation += 1;
+        self.fresh = true;
+        Ok(&self.axiom)
+    }
+}
+
+impl<T, F, I, E> Iterator for TryLSystem<T, F>
+    where T: Clone, F: FnMut(T) -> Result<I, E>, I: IntoIterator<Item = T> {
+    type Item = Result<Vec<T>, Error<E>>;
+
+    /// Yield the next iteration of the L-system, or the error that prevented it.
+    fn next(&mut self) -> Option<Result<Vec<T>, Error<E>>> {
+        if self.failed {
+            return None
+        }
+
+        if !self.fresh {
+            if let Err(e) = self.try_step() {
+                self.failed = true;
+                return Some(Err(e))
+            }
+        }
+        self.fresh = false;
+        Some(Ok(self.axiom.clone()))
+    }
+}
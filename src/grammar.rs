@@ -0,0 +1,770 @@
+//! Reading L-systems over characters from plain text.
+//!
+//! Grammars are often written down (or kept in files) in a plain text notation, rather than as
+//! Rust. The `parse` function reads an axiom and productions in that notation, producing an
+//! `LSystem` over `char` symbols whose productions are held in a `Rules` table:
+//!
+//! * the axiom is given on a line of the form `axiom: F`;
+//! * each production is given on a line of the form `F -> FF-[-F+F+F]+[+F-F-F]`, with a single
+//!   symbol as its predecessor;
+//! * whitespace between symbols is ignored, as are blank lines;
+//! * a `#` at the start of a line or after whitespace begins a comment, which runs to the end of
+//!   the line, while a `#` written straight after another symbol, as in `F#F`, is a symbol; and
+//! * symbols without a production are constants.
+//!
+//! Mistakes are reported as a `ParseError`, which gives the line and column at which they were
+//! found.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::grammar::{self, ParseErrorKind};
+//!
+//! let mut seaweed = grammar::parse("
+//!     axiom: F
+//!     F -> FF-[-F+F+F]+[+F-F-F]  # two branches
+//! ").unwrap();
+//!
+//! assert_eq!(seaweed.nth(1).unwrap().into_iter().collect::<String>(), "FF-[-F+F+F]+[+F-F-F]");
+//!
+//! let marked = grammar::parse("axiom: F#F  # a marked segment").unwrap();
+//! assert_eq!(marked.current(), ['F', '#', 'F']);
+//!
+//! let error = grammar::parse("axiom: A\nA -> AB\nB -> A\nA -> B").err().unwrap();
+//! assert_eq!((error.line, error.column), (4, 1));
+//! assert_eq!(error.kind, ParseErrorKind::DuplicatePredecessor { symbol: 'A', first: 2 });
+//! assert_eq!(error.to_string(), "4:1: `A` already has a production on line 2");
+//! ```
+//!
+//! ## Parametric Grammars
+//!
+//! The `parse_parametric` function reads a `ParametricLSystem` from the same kind of text, with
+//! each production written just as `Production` displays it, such as
+//! `A(x, y) : y <= 3 -> A(x * 2, x + y)`. Productions are tried in the order they are given.
+//!
+//! Expressions can use the arithmetic operators `+`, `-`, `*`, `/` and `^`; the comparisons `==`,
+//! `!=`, `<`, `<=`, `>` and `>=`; the logical operators `&&`, `||` and `!`; the functions listed
+//! by `Function`, such as `sqrt(x)` or `max(x, y)`; and the constants `pi`, `tau` and `e`. More
+//! constants can be defined on lines of the form `define: angle = pi / 4`, and used on any line
+//! after their definition. Arguments in the axiom can be expressions too, so long as they only
+//! use constants.
+//!
+//! As in most languages, `^` binds more tightly than unary minus, so `-2 ^ 2` is `-4`, and numbers
+//! must be finite, in literals and constants alike.
+//!
+//! `Expr` and `Production<char>` can also be parsed on their own with `str::parse`, which reads
+//! back what they display. The one exception is a number that is infinite or `NaN`, which the
+//! parser never produces itself: it is displayed as a division by zero, and read back as one.
+//!
+//! ```rust
+//! use lsystem::grammar;
+//! use lsystem::parametric::{Expr, Module, Production};
+//!
+//! let mut system = grammar::parse_parametric("
+//!     define: limit = 3
+//!     axiom: B(2) A(4, 4)
+//!     A(x, y) : y <= limit -> A(x * 2, x + y)
+//!     A(x, y) : y > limit -> B(x) A(x / y, 0)
+//!     B(x) : x < 1 -> C
+//!     B(x) : x >= 1 -> B(x - 1)
+//! ").unwrap();
+//!
+//! // Constants are replaced by their values.
+//! let production = system.productions()[0].clone();
+//! assert_eq!(production.to_string(), "A(x, y) : y <= 3 -> A(x * 2, x + y)");
+//! assert_eq!(production.to_string().parse::<Production<char>>(), Ok(production));
+//!
+//! assert_eq!(system.nth(1).unwrap(), vec!(Module::new('B', vec!(1.0)),
+//!                                         Module::new('B', vec!(4.0)),
+//!                                         Module::new('A', vec!(1.0, 0.0))));
+//!
+//! let expr: Expr = "max(x, 2) ^ 2 * pi".parse().unwrap();
+//! assert_eq!(expr.eval(&["x".to_string()], &[1.0]), 4.0 * std::f64::consts::PI);
+//! ```
+//!
+//! ```rust
+//! use lsystem::parametric::{Expr, num, param};
+//!
+//! let x = vec!("x".to_string());
+//! assert_eq!("-2 ^ 2".parse::<Expr>().unwrap().eval(&[], &[]), -4.0);
+//! assert_eq!("-x ^ 2".parse::<Expr>().unwrap().eval(&x, &[3.0]), -9.0);
+//!
+//! // A negated number is told apart from a negative one.
+//! let exprs = vec!(-num(2.0), num(-2.0), num(-2.0).pow(2.0), -num(2.0).pow(2.0),
+//!                  num(2.0).pow(-param("x")), param("x") - -num(2.0));
+//! for expr in exprs {
+//!     assert_eq!(expr.to_string().parse::<Expr>(), Ok(expr));
+//! }
+//! assert_eq!((-num(2.0)).to_string(), "-(2)");
+//! assert_eq!(num(-2.0).pow(2.0).to_string(), "(-2) ^ 2");
+//!
+//! // Infinities are written as divisions, which evaluate to the same value.
+//! assert_eq!(num(std::f64::INFINITY).to_string(), "(1 / 0)");
+//! let infinite: Expr = num(-std::f64::INFINITY).to_string().parse().unwrap();
+//! assert_eq!(infinite.eval(&[], &[]), -std::f64::INFINITY);
+//! assert!("1e999".parse::<Expr>().is_err());
+//! ```
+
+use std::error::Error;
+use std::f64::consts;
+use std::fmt;
+use std::str::FromStr;
+
+use LSystem;
+use parametric::{BinaryOp, Expr, Function, Module, ParametricLSystem, Pattern, Production,
+                 Template};
+use rules::Rules;
+
+/// The constants that every expression can use.
+const CONSTANTS: [(&str, f64); 3] = [("pi", consts::PI), ("tau", consts::TAU), ("e", consts::E)];
+
+/// The binary operators other than `^`, with each listed before any operator that it starts with.
+const OPERATORS: [BinaryOp; 12] = [
+    BinaryOp::Or, BinaryOp::And,
+    BinaryOp::Eq, BinaryOp::Ne, BinaryOp::Le, BinaryOp::Ge, BinaryOp::Lt, BinaryOp::Gt,
+    BinaryOp::Add, BinaryOp::Sub,
+    BinaryOp::Mul, BinaryOp::Div
+];
+
+/// Parse a grammar over `char` symbols, such as:
+///
+/// ```text
+/// axiom: F
+/// F -> F+F-F-F+F
+/// ```
+///
+/// See the module documentation for the details of the notation.
+pub fn parse(text: &str) -> Result<LSystem<char, Rules<char>>, ParseError> {
+    let mut axiom: Option<(usize, Vec<char>)> = None;
+    let mut rules = Rules::new();
+    // The line on which each predecessor's production was given.
+    let mut defined: Vec<(char, usize)> = Vec::new();
+
+    for (number, line, start) in lines(text) {
+        if let Some(colon) = keyword_colon(&line[start..], "axiom") {
+            if let Some((first, _)) = axiom {
+                return Err(ParseError::new(number, column(line, start),
+                                           ParseErrorKind::DuplicateAxiom { first }))
+            }
+
+            let symbols = symbols(line, start + colon + 1);
+            if symbols.is_empty() {
+                return Err(ParseError::new(number, column(line, start + colon + 1),
+                                           ParseErrorKind::EmptyAxiom))
+            }
+            axiom = Some((number, symbols.into_iter().map(|(_, symbol)| symbol).collect()));
+            continue
+        }
+
+        let arrow = match line.find("->") {
+            Some(arrow) => arrow,
+            None => {
+                return Err(ParseError::new(number, column(line, start),
+                                           ParseErrorKind::MissingArrow))
+            }
+        };
+
+        let predecessor = symbols(&line[..arrow], 0);
+        let symbol = match predecessor.len() {
+            0 => {
+                return Err(ParseError::new(number, column(line, arrow),
+                                           ParseErrorKind::MissingPredecessor))
+            },
+            1 => predecessor[0].1,
+            _ => {
+                return Err(ParseError::new(number, column(line, predecessor[1].0),
+                                           ParseErrorKind::LongPredecessor))
+            }
+        };
+
+        if let Some(&(_, first)) = defined.iter().find(|&&(defined, _)| defined == symbol) {
+            return Err(ParseError::new(number, column(line, predecessor[0].0),
+                                       ParseErrorKind::DuplicatePredecessor { symbol, first }))
+        }
+        defined.push((symbol, number));
+
+        let successor = symbols(line, arrow + 2).into_iter().map(|(_, symbol)| symbol).collect();
+        rules.insert(symbol, successor);
+    }
+
+    match axiom {
+        Some((_, axiom)) => Ok(LSystem::from_rules(axiom, rules)),
+        None => Err(missing_axiom(text))
+    }
+}
+
+/// Parse a parametric grammar over `char` symbols, such as:
+///
+/// ```text
+/// define: limit = 3
+/// axiom: B(2) A(4, 4)
+/// A(x, y) : y <= limit -> A(x * 2, x + y)
+/// A(x, y) : y > limit -> B(x) A(x / y, 0)
+/// ```
+///
+/// See the module documentation for the details of the notation.
+pub fn parse_parametric(text: &str) -> Result<ParametricLSystem<char>, ParseError> {
+    let mut constants = builtin_constants();
+    let mut axiom: Option<(usize, Vec<Module<char>>)> = None;
+    let mut productions = Vec::new();
+
+    for (number, line, start) in lines(text) {
+        let mut cursor = Cursor { number, line, pos: start };
+
+        if let Some(colon) = keyword_colon(&line[start..], "define") {
+            cursor.pos += colon + 1;
+            let (name, offset) = cursor.name()?;
+            if constants.iter().any(|(defined, _)| *defined == name) {
+                return Err(cursor.error_at(offset, ParseErrorKind::DuplicateConstant(name)))
+            }
+
+            cursor.expect("=", "`=`")?;
+            let value = cursor.expr(&Scope { params: Some(&[]), constants: &constants })?;
+            cursor.end()?;
+
+            let value = value.eval(&[], &[]);
+            if !value.is_finite() {
+                return Err(cursor.error_at(offset, ParseErrorKind::NonFiniteConstant(name)))
+            }
+            constants.push((name, value));
+        } else if let Some(colon) = keyword_colon(&line[start..], "axiom") {
+            if let Some((first, _)) = axiom {
+                return Err(cursor.error_at(start, ParseErrorKind::DuplicateAxiom { first }))
+            }
+
+            cursor.pos += colon + 1;
+            let scope = Scope { params: Some(&[]), constants: &constants };
+            let mut modules = Vec::new();
+            while !cursor.at_end() {
+                let (symbol, args) = cursor.module(&scope)?;
+                let params = args.iter().map(|arg| arg.eval(&[], &[])).collect();
+                modules.push(Module::new(symbol, params));
+            }
+
+            if modules.is_empty() {
+                return Err(cursor.error_at(cursor.pos, ParseErrorKind::EmptyAxiom))
+            }
+            axiom = Some((number, modules));
+        } else if !line.contains("->") {
+            return Err(cursor.error_at(start, ParseErrorKind::MissingArrow))
+        } else {
+            productions.push(cursor.production(&constants)?);
+        }
+    }
+
+    match axiom {
+        Some((_, axiom)) => Ok(ParametricLSystem::new(axiom, productions)),
+        None => Err(missing_axiom(text))
+    }
+}
+
+/// Every name other than those of the built-in constants is taken to be a parameter.
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Expr, ParseError> {
+        let constants = builtin_constants();
+        let mut cursor = Cursor { number: 1, line: text, pos: 0 };
+        let expr = cursor.expr(&Scope { params: None, constants: &constants })?;
+        cursor.end()?;
+        Ok(expr)
+    }
+}
+
+impl FromStr for Production<char> {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Production<char>, ParseError> {
+        let constants = builtin_constants();
+        Cursor { number: 1, line: text, pos: 0 }.production(&constants)
+    }
+}
+
+/// The lines of the text that are not blank once their comments are removed, each without its
+/// comment, and with its number (counting from one) and the byte offset of its first symbol.
+fn lines<'a>(text: &'a str) -> impl Iterator<Item = (usize, &'a str, usize)> + 'a {
+    text.lines().enumerate().filter_map(|(index, line)| {
+        let line = strip_comment(line);
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some((index + 1, line, line.len() - trimmed.len()))
+        }
+    })
+}
+
+/// The line without its comment, which starts at a `#` that begins the line or follows whitespace.
+fn strip_comment(line: &str) -> &str {
+    let mut previous = ' ';
+    for (offset, symbol) in line.char_indices() {
+        if symbol == '#' && previous.is_whitespace() {
+            return &line[..offset]
+        }
+        previous = symbol;
+    }
+    line
+}
+
+/// If the (trimmed) line starts with `keyword` and a colon, the byte offset of that colon.
+fn keyword_colon(trimmed: &str, keyword: &str) -> Option<usize> {
+    if !trimmed.starts_with(keyword) {
+        return None
+    }
+
+    let rest = &trimmed[keyword.len()..];
+    let spaces = rest.len() - rest.trim_start().len();
+    if rest[spaces..].starts_with(':') {
+        Some(keyword.len() + spaces)
+    } else {
+        None
+    }
+}
+
+/// The symbols of a line from the byte offset `start` onwards, with the byte offset of each.
+fn symbols(line: &str, start: usize) -> Vec<(usize, char)> {
+    line[start..].char_indices()
+        .filter(|&(_, symbol)| !symbol.is_whitespace())
+        .map(|(offset, symbol)| (start + offset, symbol))
+        .collect()
+}
+
+/// The column, counting characters from one, at the given byte offset of a line.
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// The error for a grammar without an axiom, which points at the end of the text.
+fn missing_axiom(text: &str) -> ParseError {
+    let lines = text.lines().count();
+    let last = text.lines().last().unwrap_or("");
+    ParseError::new(lines.max(1), last.chars().count() + 1, ParseErrorKind::MissingAxiom)
+}
+
+fn builtin_constants() -> Vec<(String, f64)> {
+    CONSTANTS.iter().map(|&(name, value)| (name.to_string(), value)).collect()
+}
+
+/// The names that an expression can refer to.
+struct Scope<'a> {
+    /// The formal parameters of the production, or `None` if every name that is not a constant
+    /// is a parameter.
+    params: Option<&'a [String]>,
+    constants: &'a [(String, f64)]
+}
+
+impl<'a> Scope<'a> {
+    /// The expression that a name stands for. Parameters hide constants of the same name.
+    fn resolve(&self, name: &str) -> Option<Expr> {
+        if let Some(params) = self.params {
+            if params.iter().any(|param| param == name) {
+                return Some(Expr::Param(name.to_string()))
+            }
+        }
+
+        match self.constants.iter().find(|(constant, _)| constant == name) {
+            Some(&(_, value)) => Some(Expr::Num(value)),
+            None if self.params.is_none() => Some(Expr::Param(name.to_string())),
+            None => None
+        }
+    }
+}
+
+/// A position in a line of a parametric grammar, from which modules, productions and expressions
+/// are read.
+struct Cursor<'a> {
+    number: usize,
+    line: &'a str,
+    pos: usize
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos == self.line.len()
+    }
+
+    fn error_at(&self, offset: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(self.number, column(self.line, offset), kind)
+    }
+
+    /// The error for finding something other than what was `expected` at the cursor.
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        self.error_at(self.pos, ParseErrorKind::Unexpected { expected, found: self.peek() })
+    }
+
+    /// Consume `token` if it comes next, returning whether it did.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str, expected: &'static str) -> Result<(), ParseError> {
+        if self.eat(token) { Ok(()) } else { Err(self.unexpected(expected)) }
+    }
+
+    fn end(&mut self) -> Result<(), ParseError> {
+        if self.at_end() { Ok(()) } else { Err(self.unexpected("the end of the line")) }
+    }
+
+    /// A name, such as a parameter, constant or function, and the byte offset at which it starts.
+    fn name(&mut self) -> Result<(String, usize), ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c.is_alphabetic() || c == '_' => (),
+            _ => return Err(self.unexpected("a name"))
+        }
+
+        let start = self.pos;
+        let rest = self.rest();
+        self.pos += rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+        Ok((self.line[start..self.pos].to_string(), start))
+    }
+
+    fn symbol(&mut self) -> Result<char, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if !"(),:".contains(c) => {
+                self.pos += c.len_utf8();
+                Ok(c)
+            },
+            _ => Err(self.unexpected("a symbol"))
+        }
+    }
+
+    /// A symbol, followed by its arguments in parentheses if it has any.
+    fn module(&mut self, scope: &Scope) -> Result<(char, Vec<Expr>), ParseError> {
+        let symbol = self.symbol()?;
+        if self.peek() != Some('(') {
+            return Ok((symbol, Vec::new()))
+        }
+        self.pos += 1;
+        Ok((symbol, self.args(scope)?))
+    }
+
+    /// Expressions separated by commas, up to and including the closing parenthesis.
+    fn args(&mut self, scope: &Scope) -> Result<Vec<Expr>, ParseError> {
+        let mut args = Vec::new();
+        if self.eat(")") {
+            return Ok(args)
+        }
+
+        loop {
+            args.push(self.expr(scope)?);
+            if self.eat(")") {
+                return Ok(args)
+            }
+            self.expect(",", "`,` or `)`")?;
+        }
+    }
+
+    fn production(&mut self, constants: &[(String, f64)])
+                  -> Result<Production<char>, ParseError> {
+        let symbol = self.symbol()?;
+        let mut params: Vec<String> = Vec::new();
+
+        if self.peek() == Some('(') {
+            self.pos += 1;
+            while !self.eat(")") {
+                if !params.is_empty() {
+                    self.expect(",", "`,` or `)`")?;
+                }
+
+                let (name, offset) = self.name()?;
+                if params.contains(&name) {
+                    return Err(self.error_at(offset, ParseErrorKind::DuplicateParameter(name)))
+                }
+                params.push(name);
+            }
+        }
+
+        let (guard, successor) = {
+            let scope = Scope { params: Some(&params), constants };
+            let guard = if self.eat(":") { Some(self.expr(&scope)?) } else { None };
+            self.expect("->", "`->`")?;
+
+            let mut successor = Vec::new();
+            while !self.at_end() {
+                let (symbol, args) = self.module(&scope)?;
+                successor.push(Template::new(symbol, args));
+            }
+            (guard, successor)
+        };
+
+        Ok(Production { predecessor: Pattern { symbol, params }, guard, successor })
+    }
+
+    fn expr(&mut self, scope: &Scope) -> Result<Expr, ParseError> {
+        self.binary(scope, 1)
+    }
+
+    /// An expression whose binary operators, other than `^`, bind at least as tightly as `min`.
+    /// These operators are all left-associative.
+    fn binary(&mut self, scope: &Scope, min: u8) -> Result<Expr, ParseError> {
+        let mut lhs = self.unary(scope)?;
+
+        while let Some(op) = self.operator() {
+            if op.precedence() < min {
+                break
+            }
+            self.pos += op.symbol().len();
+            let rhs = self.binary(scope, op.precedence() + 1)?;
+            lhs = lhs.binary(op, rhs);
+        }
+        Ok(lhs)
+    }
+
+    /// The binary operator, other than `^`, that comes next, without consuming it.
+    fn operator(&mut self) -> Option<BinaryOp> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        if rest.starts_with("->") {
+            return None
+        }
+        OPERATORS.iter().cloned().find(|op| rest.starts_with(op.symbol()))
+    }
+
+    /// Exponentiation, which is right-associative. As in `Display`, `^` binds more tightly than a
+    /// unary operator on its left, so `-x ^ 2` is `-(x ^ 2)`, but its exponent may start with one,
+    /// as in `2 ^ -x`.
+    fn power(&mut self, scope: &Scope) -> Result<Expr, ParseError> {
+        let base = self.primary(scope)?;
+        if self.eat("^") {
+            Ok(base.pow(self.unary(scope)?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn unary(&mut self, scope: &Scope) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+
+        if rest.starts_with('-') && !rest.starts_with("->") {
+            self.pos += 1;
+            self.skip_whitespace();
+            // A negated number is read as a negative number, which is how one is displayed,
+            // unless it is raised to a power.
+            let literal = self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.');
+            return match self.unary(scope)? {
+                Expr::Num(value) if literal => Ok(Expr::Num(-value)),
+                operand => Ok(-operand)
+            }
+        }
+
+        if rest.starts_with('!') {
+            self.pos += 1;
+            return Ok(self.unary(scope)?.negate())
+        }
+        self.power(scope)
+    }
+
+    fn primary(&mut self, scope: &Scope) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.expr(scope)?;
+                self.expect(")", "`)`")?;
+                Ok(expr)
+            },
+            Some(c) if c.is_ascii_digit() || c == '.' => Ok(Expr::Num(self.number()?)),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let (name, start) = self.name()?;
+                if self.peek() == Some('(') {
+                    self.pos += 1;
+                    return self.call(scope, name, start)
+                }
+
+                match scope.resolve(&name) {
+                    Some(expr) => Ok(expr),
+                    None => Err(self.error_at(start, ParseErrorKind::UnknownName(name)))
+                }
+            },
+            _ => Err(self.unexpected("an expression"))
+        }
+    }
+
+    /// The arguments of a call of the function called `name`, which starts at the byte offset
+    /// `start`.
+    fn call(&mut self, scope: &Scope, name: String, start: usize) -> Result<Expr, ParseError> {
+        let function = match Function::from_name(&name) {
+            Some(function) => function,
+            None => return Err(self.error_at(start, ParseErrorKind::UnknownFunction(name)))
+        };
+
+        let args = self.args(scope)?;
+        if args.len() != function.arity() {
+            let found = args.len();
+            return Err(self.error_at(start, ParseErrorKind::WrongArgumentCount { function, found }))
+        }
+        Ok(Expr::call(function, args))
+    }
+
+    /// A number such as `42`, `0.5` or `1e-3`.
+    fn number(&mut self) -> Result<f64, ParseError> {
+        let start = self.pos;
+        let rest = self.rest();
+        let mut length = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+
+        // An `e` is only part of the number if digits follow it, as in `1e-3`.
+        let exponent = &rest[length..];
+        if exponent.starts_with('e') || exponent.starts_with('E') {
+            let sign = if exponent[1..].starts_with('+') || exponent[1..].starts_with('-') { 2 }
+                       else { 1 };
+            let digits = exponent[sign..].find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(exponent.len() - sign);
+            if digits > 0 {
+                length += sign + digits;
+            }
+        }
+
+        self.pos += length;
+        match self.line[start..self.pos].parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(self.error_at(start, ParseErrorKind::InvalidNumber))
+        }
+    }
+}
+
+/// A mistake in the text of a grammar, along with where it was found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The line on which the mistake was found, counting from one.
+    pub line: usize,
+    /// The column at which the mistake was found, counting characters from one.
+    pub column: usize,
+    /// The mistake itself.
+    pub kind: ParseErrorKind
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { line, column, kind }
+    }
+}
+
+/// The mistakes that can be made in the text of a grammar.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseErrorKind {
+    /// The grammar has no axiom.
+    MissingAxiom,
+    /// The grammar has more than one axiom. The first was given on line `first`.
+    DuplicateAxiom {
+        /// The line on which the first axiom was given.
+        first: usize
+    },
+    /// The axiom has no symbols.
+    EmptyAxiom,
+    /// A line is neither the axiom nor a production.
+    MissingArrow,
+    /// A production has no predecessor.
+    MissingPredecessor,
+    /// A production has more than one symbol as its predecessor.
+    LongPredecessor,
+    /// A symbol has more than one production.
+    DuplicatePredecessor {
+        /// The symbol.
+        symbol: char,
+        /// The line on which its first production was given.
+        first: usize
+    },
+    /// A parametric grammar has something other than what was expected.
+    Unexpected {
+        /// What was expected, such as "`)`" or "an expression".
+        expected: &'static str,
+        /// The character found instead, or `None` at the end of the line.
+        found: Option<char>
+    },
+    /// A number could not be read, or is too large to be finite.
+    InvalidNumber,
+    /// An expression refers to a name that is neither a parameter nor a constant.
+    UnknownName(String),
+    /// An expression calls a function that does not exist.
+    UnknownFunction(String),
+    /// A function is called with the wrong number of arguments.
+    WrongArgumentCount {
+        /// The function.
+        function: Function,
+        /// The number of arguments it was given.
+        found: usize
+    },
+    /// A formal parameter name appears more than once in a predecessor.
+    DuplicateParameter(String),
+    /// A constant is defined more than once, or has the name of a built-in constant.
+    DuplicateConstant(String),
+    /// A constant is defined to be infinite or `NaN`.
+    NonFiniteConstant(String)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::MissingAxiom => {
+                write!(f, "no axiom was given; expected a line such as `axiom: F`")
+            },
+            ParseErrorKind::DuplicateAxiom { first } => {
+                write!(f, "the axiom was already given on line {}", first)
+            },
+            ParseErrorKind::EmptyAxiom => write!(f, "the axiom has no symbols"),
+            ParseErrorKind::MissingArrow => {
+                write!(f, "expected a production such as `F -> FF`, or the axiom")
+            },
+            ParseErrorKind::MissingPredecessor => write!(f, "the production has no predecessor"),
+            ParseErrorKind::LongPredecessor => {
+                write!(f, "the predecessor of a production must be a single symbol")
+            },
+            ParseErrorKind::DuplicatePredecessor { symbol, first } => {
+                write!(f, "`{}` already has a production on line {}", symbol, first)
+            },
+            ParseErrorKind::Unexpected { expected, found: Some(found) } => {
+                write!(f, "expected {}, found `{}`", expected, found)
+            },
+            ParseErrorKind::Unexpected { expected, found: None } => {
+                write!(f, "expected {}, found the end of the line", expected)
+            },
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::UnknownName(ref name) => {
+                write!(f, "`{}` is neither a parameter nor a constant", name)
+            },
+            ParseErrorKind::UnknownFunction(ref name) => {
+                write!(f, "there is no function called `{}`", name)
+            },
+            ParseErrorKind::WrongArgumentCount { function, found } => {
+                write!(f, "`{}` takes {} argument{}, not {}", function.name(), function.arity(),
+                       if function.arity() == 1 { "" } else { "s" }, found)
+            },
+            ParseErrorKind::DuplicateParameter(ref name) => {
+                write!(f, "parameter `{}` is declared more than once", name)
+            },
+            ParseErrorKind::DuplicateConstant(ref name) => {
+                write!(f, "the constant `{}` is already defined", name)
+            },
+            ParseErrorKind::NonFiniteConstant(ref name) => {
+                write!(f, "the constant `{}` is not a finite number", name)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,223 @@
+//! Closed-form analysis of the growth of deterministic, context-free L-systems.
+//!
+//! When every symbol of a finite alphabet has exactly one successor, the number of times each
+//! symbol occurs in a generation (its Parikh vector) depends only on the Parikh vector of the
+//! previous generation. If `M` is the growth matrix of the L-system, whose entry `M[i][j]` counts
+//! the occurrences of symbol `j` in the successor of symbol `i`, and `v` is the Parikh vector of
+//! the axiom, then the Parikh vector of generation `n` is `v M^n`. Its length is the sum of the
+//! entries.
+//!
+//! This makes it possible to know exactly how long a generation will be, and what it contains,
+//! without deriving it. Since lengths usually grow exponentially, all counts are arbitrary
+//! precision `BigUint`s.
+//!
+//! ## An Example
+//!
+//! The lengths of the generations of Lindenmayer's algae are the Fibonacci numbers:
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//! use lsystem::growth::BigUint;
+//!
+//! let mut algae = LSystem::new(vec!('B'), |x| match x {
+//!     'A' => vec!('A', 'B'),
+//!     _   => vec!('A')
+//! });
+//!
+//! let growth = algae.growth(vec!('A', 'B'));
+//! assert_eq!(growth.matrix(), vec!(vec!(1, 1), vec!(1, 0)));
+//!
+//! assert_eq!(growth.length(&['B'], 4), BigUint::from(5u32));
+//! assert_eq!(growth.parikh(&['B'], 4), vec!(BigUint::from(3u32), BigUint::from(2u32)));
+//! assert_eq!(growth.length(&['B'], 100).to_string(), "573147844013817084101");
+//! ```
+//!
+//! ## Random Access
+//!
+//! The same idea allows any single symbol of a generation to be found without deriving the rest.
+//! Knowing the length that each symbol expands to after `d` more steps, `Growth::symbol_at`
+//! skips over whole subtrees of the derivation, descending from the axiom to the symbol at the
+//! requested position one generation at a time.
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//!
+//! let mut koch = LSystem::new(vec!('F'), |x| match x {
+//!     'F' => "F+F-F-F+F".chars().collect(),
+//!     c   => vec!(c)
+//! });
+//!
+//! let growth = koch.growth(vec!('F', '+', '-'));
+//! let generation = koch.nth(4).unwrap();
+//!
+//! for k in 0..generation.len() {
+//!     assert_eq!(growth.symbol_at(&['F'], 4, k), Some(&generation[k]));
+//! }
+//! assert_eq!(growth.symbol_at(&['F'], 4, generation.len()), None);
+//!
+//! // Generation 30 has more symbols than fit in a `u64`, but is never built.
+//! assert_eq!(growth.symbol_at(&['F'], 30, 123456789u64), Some(&'+'));
+//! ```
+
+pub use num_bigint::BigUint;
+
+use Rewrite;
+
+/// The growth matrix of a deterministic L-system over a finite alphabet.
+///
+/// This is usually created with `LSystem::growth`.
+pub struct Growth<T> {
+    alphabet: Vec<T>,
+    successors: Vec<Vec<usize>>
+}
+
+impl<T> Growth<T> where T: Clone + PartialEq {
+    /// Builds the growth matrix by applying the production rules once to every symbol of the
+    /// alphabet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a successor contains a symbol that is not part of the alphabet.
+    pub fn new<R>(alphabet: Vec<T>, rules: &mut R) -> Growth<T> where R: Rewrite<T> {
+        let successors = alphabet.iter().map(|symbol| {
+            rules.rewrite(symbol.clone()).into_iter().map(|s| {
+                match alphabet.iter().position(|a| *a == s) {
+                    Some(index) => index,
+                    None => panic!("successor contains a symbol outside of the alphabet")
+                }
+            }).collect()
+        }).collect();
+
+        Growth { alphabet, successors }
+    }
+
+    /// The alphabet, in the order used by the rows and columns of the matrix and by Parikh
+    /// vectors.
+    pub fn alphabet(&self) -> &[T] {
+        &self.alphabet
+    }
+
+    /// The growth matrix, whose entry at row `i` and column `j` is the number of times symbol `j`
+    /// of the alphabet occurs in the successor of symbol `i`.
+    pub fn matrix(&self) -> Vec<Vec<usize>> {
+        self.successors.iter().map(|successor| {
+            let mut row = vec!(0; self.alphabet.len());
+            for &index in successor.iter() {
+                row[index] += 1;
+            }
+            row
+        }).collect()
+    }
+
+    /// The number of times each symbol of the alphabet occurs in generation `n` of the L-system
+    /// with the given axiom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the axiom contains a symbol that is not part of the alphabet.
+    pub fn parikh(&self, axiom: &[T], n: usize) -> Vec<BigUint> {
+        let mut counts = vec!(BigUint::from(0u32); self.alphabet.len());
+        for symbol in axiom.iter() {
+            counts[self.index_of(symbol)] += 1u32;
+        }
+
+        // Raise the matrix to the nth power by repeated squaring, multiplying the vector by each
+        // power of two that makes up `n`. Powers of the same matrix commute, so the order in
+        // which they are applied does not matter.
+        let mut power: Vec<Vec<BigUint>> = self.matrix().into_iter().map(|row| {
+            row.into_iter().map(BigUint::from).collect()
+        }).collect();
+        let mut n = n;
+
+        while n > 0 {
+            if n & 1 == 1 {
+                counts = multiply(&[counts], &power).pop().unwrap();
+            }
+            n >>= 1;
+            if n > 0 {
+                power = multiply(&power, &power);
+            }
+        }
+        counts
+    }
+
+    /// The length of generation `n` of the L-system with the given axiom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the axiom contains a symbol that is not part of the alphabet.
+    pub fn length(&self, axiom: &[T], n: usize) -> BigUint {
+        self.parikh(axiom, n).into_iter().sum()
+    }
+
+    /// The symbol at position `k` of generation `n` of the L-system with the given axiom, or
+    /// `None` if the generation is not that long.
+    ///
+    /// The generation is never built: instead, the lengths that each symbol of the alphabet
+    /// expands to are used to descend from the axiom directly to the requested symbol, in time
+    /// proportional to `n` times the length of the successors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the axiom contains a symbol that is not part of the alphabet.
+    pub fn symbol_at<K>(&self, axiom: &[T], n: usize, k: K) -> Option<&T> where K: Into<BigUint> {
+        let lengths = self.expansion_lengths(n);
+        let mut k = k.into();
+
+        // Find the symbol of the axiom whose expansion contains position `k`, then the symbol of
+        // its successor whose expansion (one step shorter) does, and so on until no steps remain.
+        let mut candidates: Vec<usize> = axiom.iter().map(|s| self.index_of(s)).collect();
+        let mut depth = n;
+
+        loop {
+            let mut found = None;
+            for index in candidates {
+                let length = &lengths[depth][index];
+                if k < *length {
+                    found = Some(index);
+                    break
+                }
+                k -= length;
+            }
+
+            let index = found?;
+            if depth == 0 {
+                return Some(&self.alphabet[index])
+            }
+            candidates = self.successors[index].clone();
+            depth -= 1;
+        }
+    }
+
+    /// The length that each symbol of the alphabet expands to after `d` steps, for every `d` from
+    /// zero to `n` inclusive.
+    fn expansion_lengths(&self, n: usize) -> Vec<Vec<BigUint>> {
+        let mut lengths = vec!(vec!(BigUint::from(1u32); self.alphabet.len()));
+
+        for depth in 0..n {
+            let next = self.successors.iter().map(|successor| {
+                successor.iter().map(|&index| &lengths[depth][index]).sum()
+            }).collect();
+            lengths.push(next);
+        }
+        lengths
+    }
+
+    fn index_of(&self, symbol: &T) -> usize {
+        match self.alphabet.iter().position(|a| a == symbol) {
+            Some(index) => index,
+            None => panic!("axiom contains a symbol outside of the alphabet")
+        }
+    }
+}
+
+/// Multiply two matrices, given as lists of rows.
+fn multiply(lhs: &[Vec<BigUint>], rhs: &[Vec<BigUint>]) -> Vec<Vec<BigUint>> {
+    let columns = rhs.first().map_or(0, |row| row.len());
+
+    lhs.iter().map(|row| {
+        (0..columns).map(|j| {
+            row.iter().zip(rhs.iter()).map(|(a, rhs_row)| a * &rhs_row[j]).sum()
+        }).collect()
+    }).collect()
+}
@@ -0,0 +1,84 @@
+//! L-systems whose productions know which generation is being rewritten, and where in the string
+//! each symbol sits.
+//!
+//! Rules for `LSystem` only ever see a single symbol, so growth that depends on the age of the
+//! structure or on the position of a cell along it has to be tracked with counters captured by
+//! the closure. The `IndexedLSystem` type instead passes a `Position` alongside every symbol.
+//!
+//! ## An Example
+//!
+//! Consider a filament that grows only at its tip, and only for its first two generations:
+//!
+//! ```rust
+//! use lsystem::indexed::IndexedLSystem;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Cell { A }
+//!
+//! let mut filament = IndexedLSystem::new(vec!(Cell::A), |x, pos| {
+//!     if pos.generation < 2 && pos.index == pos.length - 1 {
+//!         vec!(x.clone(), x)
+//!     } else {
+//!         vec!(x)
+//!     }
+//! });
+//!
+//! assert_eq!(filament.nth(5).unwrap(), vec!(Cell::A, Cell::A, Cell::A));
+//! ```
+
+use std::mem;
+
+/// Where a symbol sits in the derivation at the moment it is rewritten.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    /// The index of the generation being rewritten, where the axiom is generation zero.
+    pub generation: usize,
+    /// The index of the symbol within that generation.
+    pub index: usize,
+    /// The total number of symbols in that generation.
+    pub length: usize
+}
+
+/// Create a Lindenmayer System defined by an axiom of type `Vec<T>` and a rule function (or
+/// closure) which maps values of type `T`, along with their `Position`, to vectors of values of
+/// type `T`.
+///
+/// This type behaves exactly like `LSystem`: the first item yielded by the iterator is the axiom
+/// itself, and every subsequent item is the next generation. The iterator is never exhausted.
+pub struct IndexedLSystem<T, F> where F: FnMut(T, Position) -> Vec<T> {
+    axiom: Vec<T>,
+    rules: F,
+    generation: usize,
+    zeroth: bool
+}
+
+impl<T, F> IndexedLSystem<T, F> where F: FnMut(T, Position) -> Vec<T> {
+    /// Creates a new representation of an L-system with the given axiom and position-aware
+    /// production rules.
+    pub fn new(axiom: Vec<T>, rules: F) -> IndexedLSystem<T, F> {
+        IndexedLSystem { axiom, rules, generation: 0, zeroth: true }
+    }
+}
+
+impl<T, F> Iterator for IndexedLSystem<T, F> where T: Clone, F: FnMut(T, Position) -> Vec<T> {
+    type Item = Vec<T>;
+
+    /// Yield the next iteration of the L-system by rewriting the current axiom's contents using
+    /// the production rules.
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.zeroth {
+            self.zeroth = false;
+            return Some(self.axiom.clone())
+        }
+
+        let old_axiom = mem::take(&mut self.axiom);
+        let length = old_axiom.len();
+
+        for (index, element) in old_axiom.into_iter().enumerate() {
+            let position = Position { generation: self.generation, index, length };
+            self.axiom.extend((self.rules)(element, position));
+        }
+        self.generation += 1;
+        Some(self.axiom.clone())
+    }
+}
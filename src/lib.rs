@@ -60,7 +60,71 @@
 //!            vec!(Algae::A, Algae::B, Algae::A, Algae::A, Algae::B))
 //! ```
 
+extern crate num_bigint;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
+use std::convert::Infallible;
+use std::hash::Hash;
 use std::mem;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use compressed::CompressedGeneration;
+use error::Error;
+use expand::Expand;
+use growth::Growth;
+use limits::{LimitExceeded, Limits};
+use memo::Memo;
+use phases::Decompose;
+use provenance::{History, Provenance};
+use prune::Pruning;
+
+pub mod compressed;
+pub mod context;
+pub mod cycle;
+pub mod error;
+pub mod expand;
+pub mod fallible;
+pub mod grammar;
+pub mod growth;
+pub mod indexed;
+pub mod limits;
+pub mod memo;
+pub mod notation;
+pub mod parametric;
+pub mod phases;
+pub mod provenance;
+pub mod prune;
+pub mod rules;
+pub mod stochastic;
+pub mod successor;
+pub mod table;
+
+/// Production rules which rewrite a single symbol into a sequence of symbols.
+///
+/// This is implemented for every function or closure of type `FnMut(T) -> I`, where `I` is any
+/// `IntoIterator<Item = T>`. Rules are therefore not restricted to returning a `Vec<T>`: they can
+/// return an `Option<T>`, an iterator, or a `successor::Successor`, none of which need to
+/// allocate.
+pub trait Rewrite<T> {
+    /// The sequence of symbols that replaces a rewritten symbol.
+    type Output: IntoIterator<Item = T>;
+
+    /// Rewrite a single symbol.
+    fn rewrite(&mut self, symbol: T) -> Self::Output;
+}
+
+impl<T, F, I> Rewrite<T> for F where F: FnMut(T) -> I, I: IntoIterator<Item = T> {
+    type Output = I;
+
+    fn rewrite(&mut self, symbol: T) -> I {
+        self(symbol)
+    }
+}
 
 /// Create the Lindenmayer System defined by an axiom of type `Vec<T>`, a rule function (or
 /// closure) which maps values of type `T` to vectors of values of type `T`, and the set of all
@@ -72,48 +136,512 @@ use std::mem;
 /// 2. An axiom composed of letters of this alphabet; and
 /// 3. A set of "production" rules for transforming sets of letters into one another.
 ///
-/// This definition satisfies these requirements by taking the universe of the values of type `T`
-/// as the alphabet, one specific vector of values of type `T` as the axiom, and a function or
-/// closure of type `FnMut(T) -> Vec<T>` for handling any transformations. This is really just a
-/// way of using Rust's type system to express a formal grammar in a very concise way. And while
-/// there's no reason one could not use regular types (like `int` or `&str`) here, this method
+/// This definition satisfies these requirements by taking the universe of the values of type `T` as
+/// the alphabet, one specific vector of values of type `T` as the axiom, and a function or closure
+/// of type `FnMut(T) -> Vec<T>` for handling any transformations. (In fact the rules may return
+/// anything that can be iterated over to produce values of type `T`; see `Rewrite`.) This is really
+/// just a way of using Rust's type system to express a formal grammar in a very concise way. And
+/// while there's no reason one could not use regular types (like `int` or `&str`) here, this method
 /// really comes into its own through the use of `enum`s.
 ///
 /// Since this type implements the normal iterator trait, it can be used in many idiomatic ways.
 /// But be warned: the iterator will never be exhausted, so any loops must be broken manually.
-pub struct LSystem<T, F: FnMut(T) -> Vec<T>> {
+///
+/// Each item yielded by the iterator is a copy of the current generation, which can be expensive
+/// for large strings. When a copy is not needed, use `step` to advance the L-system and `current`
+/// to look at the result by reference instead:
+///
+/// ```rust
+/// use lsystem::LSystem;
+///
+/// let mut algae = LSystem::new(vec!('B'), |x| match x {
+///     'A' => vec!('A', 'B'),
+///     _   => vec!('A')
+/// });
+///
+/// while algae.generation() < 4 {
+///     algae.step();
+/// }
+/// assert_eq!(algae.current(), &['A', 'B', 'A', 'A', 'B']);
+/// ```
+///
+/// To guard against generations that grow out of control, an L-system can also be given `Limits`
+/// on its derivation. See the `limits` module for details.
+pub struct LSystem<T, R: Rewrite<T>> {
     axiom: Vec<T>,
-    rules: F,
-    zeroth: bool
+    rules: R,
+    limits: Limits,
+    pruning: Option<Pruning<T>>,
+    history: History,
+    generation: usize,
+    fresh: bool
 }
 
-impl<T, F> LSystem<T, F> where F: FnMut(T) -> Vec<T> {
+impl<T, F, I> LSystem<T, F> where F: FnMut(T) -> I, I: IntoIterator<Item = T> {
     /// Creates a new representation of an L-system with the given axiom and production rules.
     pub fn new(axiom: Vec<T>, rules: F) -> LSystem<T, F> {
-        LSystem { axiom: axiom, rules: rules, zeroth: true }
+        LSystem::from_rules(axiom, rules)
+    }
+}
+
+impl<T, R> LSystem<T, R> where R: Rewrite<T> {
+    /// Creates a new representation of an L-system with the given axiom and any kind of
+    /// production rules, such as a `Rules` table. Closures are better passed to `new`, which can
+    /// infer the types of their arguments.
+    pub fn from_rules(axiom: Vec<T>, rules: R) -> LSystem<T, R> {
+        LSystem {
+            axiom,
+            rules,
+            limits: Limits::new(),
+            pruning: None,
+            history: History::new(),
+            generation: 0,
+            fresh: true
+        }
+    }
+
+    /// The production rules of the L-system.
+    pub fn rules(&self) -> &R {
+        &self.rules
+    }
+
+    /// Limit the derivation of this L-system, replacing any existing limits.
+    pub fn with_limits(mut self, limits: Limits) -> LSystem<T, R> {
+        self.limits = limits;
+        self
+    }
+
+    /// Prune each new generation once it has been rewritten, replacing any existing pruning. See
+    /// the `prune` module for details.
+    pub fn with_pruning(mut self, pruning: Pruning<T>) -> LSystem<T, R> {
+        self.pruning = Some(pruning);
+        self
+    }
+
+    /// Apply decomposition rules to the symbols produced by each step, until none of them apply.
+    /// These return `None` for symbols they leave as they are. See the `phases` module for
+    /// details.
+    pub fn with_decomposition<D>(self, decomposition: D) -> LSystem<T, Decompose<R, D>>
+        where D: FnMut(&T) -> Option<Vec<T>> {
+        LSystem {
+            axiom: self.axiom,
+            rules: Decompose::new(self.rules, decomposition),
+            limits: self.limits,
+            pruning: self.pruning,
+            history: self.history,
+            generation: self.generation,
+            fresh: self.fresh
+        }
+    }
+
+    /// Record the provenance of each symbol from the current generation onwards. See the
+    /// `provenance` module for details.
+    pub fn with_provenance(mut self, provenance: Provenance) -> LSystem<T, R> {
+        self.history.start(provenance);
+        self
+    }
+
+    /// For each symbol of the current generation, the index of the symbol in the previous
+    /// generation that produced it. This is `None` unless provenance is being recorded, and until
+    /// the first step after recording began.
+    pub fn parents(&self) -> Option<&[usize]> {
+        self.history.parents()
+    }
+
+    /// The index of each ancestor of the symbol at `index` in the current generation, from the
+    /// generation at which provenance started being recorded (usually the axiom) up to the
+    /// previous generation. This is `None` unless the full `Provenance::Lineage` is being
+    /// recorded, or if there is no symbol at `index`.
+    pub fn lineage(&self, index: usize) -> Option<Vec<usize>> {
+        if index >= self.axiom.len() {
+            return None
+        }
+        self.history.lineage(index)
+    }
+
+    /// The current generation of the L-system, which is the axiom until the first call to `step`.
+    pub fn current(&self) -> &[T] {
+        &self.axiom
+    }
+
+    /// The index of the current generation, where the axiom is generation zero.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Apply interpretation rules to the current generation, producing the string that should be
+    /// drawn in its place. These return `None` for symbols they leave as they are. The L-system
+    /// itself is unaffected. See the `phases` module for details.
+    pub fn interpret<H>(&self, rules: H) -> Vec<T> where T: Clone, H: FnMut(&T) -> Option<Vec<T>> {
+        phases::interpret(&self.axiom, rules)
+    }
+
+    /// Rewrite the current generation's contents using the production rules, and return the
+    /// result by reference.
+    ///
+    /// The new generation has not yet been yielded by the iterator, so a subsequent call to
+    /// `next` will return a copy of it rather than advancing again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the L-system has `Limits` and one of them is exceeded. Use `try_step` to handle
+    /// this case instead.
+    pub fn step(&mut self) -> &[T] {
+        let old_axiom = mem::take(&mut self.axiom);
+        let bytes = old_axiom.capacity() * mem::size_of::<T>();
+
+        if let Err(e) = self.rewrite(old_axiom, bytes) {
+            panic!("{}", e);
+        }
+        &self.axiom
+    }
+
+    /// Rewrite the current generation like `step`, unless doing so would exceed one of the
+    /// L-system's `Limits`. In that case the current generation is left as it was, and the limit
+    /// that was exceeded is returned.
+    pub fn try_step(&mut self) -> Result<&[T], LimitExceeded> where T: Clone {
+        if self.limits.is_unlimited() {
+            return Ok(self.step())
+        }
+
+        // The old generation must survive a failed step, so rewrite copies of its symbols.
+        let old_axiom = mem::take(&mut self.axiom);
+        let bytes = old_axiom.capacity() * mem::size_of::<T>();
+
+        match self.rewrite(old_axiom.iter().cloned(), bytes) {
+            Ok(()) => Ok(&self.axiom),
+            Err(e) => {
+                self.axiom = old_axiom;
+                Err(e)
+            }
+        }
+    }
+
+    /// Build the next generation from the symbols of the previous one, which still occupies
+    /// `previous_bytes` of storage, checking the limits as it grows.
+    fn rewrite<I>(&mut self, old_axiom: I, previous_bytes: usize) -> Result<(), LimitExceeded>
+        where I: IntoIterator<Item = T> {
+        let mut parents = Vec::new();
+        let recording = if self.history.is_recording() { Some(&mut parents) } else { None };
+        let rules = &mut self.rules;
+
+        let rewritten = derive(&mut self.axiom, old_axiom, previous_bytes, &self.limits,
+                               self.generation + 1, recording,
+                               |element| Ok::<_, Infallible>(rules.rewrite(element)));
+        match rewritten {
+            Ok(()) => {
+                self.finish(parents);
+                Ok(())
+            },
+            Err(Error::Limit(e)) => Err(e),
+            Err(Error::Rule { error, .. }) => match error {}
+        }
+    }
+
+    /// Prune the newly rewritten generation and record its parents, then make it current.
+    fn finish(&mut self, mut parents: Vec<usize>) {
+        self.prune(&mut parents);
+        self.history.record(parents);
+        self.generation += 1;
+        self.fresh = true;
+    }
+
+    /// Apply any pruning to the newly rewritten generation, along with the parents recorded for it.
+    fn prune(&mut self, parents: &mut Vec<usize>) {
+        if let Some(ref pruning) = self.pruning {
+            let kept = pruning.kept(&self.axiom);
+            prune::retain(&mut self.axiom, &kept);
+            if !parents.is_empty() {
+                prune::retain(parents, &kept);
+            }
+        }
+    }
+
+    /// Lazily produce the symbols of the generation `depth` steps after the current one, without
+    /// ever holding that generation in memory.
+    ///
+    /// The symbols are found by expanding each symbol of the current generation depth-first, so
+    /// memory use is proportional to `depth` rather than to the length of the result. The
+    /// L-system itself is not advanced.
+    ///
+    /// ```rust
+    /// use lsystem::LSystem;
+    ///
+    /// let mut koch = LSystem::new(vec!('F'), |x| match x {
+    ///     'F' => "F+F-F-F+F".chars().collect(),
+    ///     c   => vec!(c)
+    /// });
+    ///
+    /// // Count the segments of the eighth generation, one symbol at a time.
+    /// assert_eq!(koch.expand(8).filter(|&c| c == 'F').count(), 390625);
+    /// assert_eq!(koch.expand(3).collect::<Vec<_>>(), koch.nth(3).unwrap());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the L-system prunes its generations, since each symbol is expanded without
+    /// seeing the branch around it.
+    pub fn expand(&mut self, depth: usize) -> Expand<'_, T, R> {
+        self.forbid_pruning("expand");
+        Expand::new(&self.axiom, &mut self.rules, depth)
+    }
+
+    /// Build the growth matrix of the production rules over the given alphabet, which can be used
+    /// to compute the length and symbol counts of any generation without deriving it.
+    ///
+    /// The rules are applied once to each symbol of the alphabet, so they should be
+    /// deterministic. See the `growth` module for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a successor contains a symbol that is not part of the alphabet, or if the
+    /// L-system prunes its generations, since cuts remove symbols the matrix would count.
+    pub fn growth(&mut self, alphabet: Vec<T>) -> Growth<T> where T: Clone + PartialEq {
+        self.forbid_pruning("growth");
+        Growth::new(alphabet, &mut self.rules)
+    }
+
+    /// Convert this L-system into a memoising expander, whose axiom is the current generation.
+    ///
+    /// The rules should be deterministic, since each symbol is only ever rewritten once. See the
+    /// `memo` module for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the L-system prunes its generations, since a cached expansion cannot depend on
+    /// the branch around it.
+    pub fn memoized(self) -> Memo<T, R> where T: Clone + Eq + Hash {
+        self.forbid_pruning("memoized");
+        Memo::new(self.axiom, self.rules)
+    }
+
+    /// Build the generation `depth` steps after the current one as a `CompressedGeneration`,
+    /// without ever holding it as a flat string. The L-system itself is not advanced.
+    ///
+    /// The rules should be deterministic, since each symbol is only ever rewritten once. See the
+    /// `compressed` module for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the generation does not fit in a `u64`, or if the L-system prunes
+    /// its generations, since a shared expansion cannot depend on the branch around it.
+    pub fn compressed(&mut self, depth: usize) -> CompressedGeneration<T>
+        where T: Clone + Eq + Hash {
+        self.forbid_pruning("compressed");
+        CompressedGeneration::new(&self.axiom, &mut self.rules, depth)
+    }
+
+    /// Reject pruning in methods that expand each symbol on its own. A cut removes the rest of
+    /// its branch, which lies outside the expansion of the symbol that produced it.
+    fn forbid_pruning(&self, method: &str) {
+        if self.pruning.is_some() {
+            panic!("`LSystem::{}` cannot be used with pruning", method);
+        }
+    }
+}
+
+/// Rewrite each symbol of the previous generation with `rewrite`, appending its successor to
+/// `next`, and the position of the symbol to `parents` once for each symbol of its successor.
+///
+/// The previous generation still occupies `previous_bytes` of storage, and `generation` is the
+/// index of the generation being produced, which is checked against `limits` as it grows.
+pub(crate) fn derive<T, I, S, J, E>(next: &mut Vec<T>, previous: I, previous_bytes: usize,
+                                    limits: &Limits, generation: usize,
+                                    mut parents: Option<&mut Vec<usize>>, mut rewrite: S)
+                                    -> Result<(), Error<E>>
+    where I: IntoIterator<Item = T>, S: FnMut(T) -> Result<J, E>, J: IntoIterator<Item = T> {
+    let unlimited = limits.is_unlimited();
+
+    for (position, element) in previous.into_iter().enumerate() {
+        match rewrite(element) {
+            Ok(successor) => next.extend(successor),
+            Err(error) => return Err(Error::Rule { generation, position, error })
+        }
+
+        if let Some(ref mut parents) = parents {
+            parents.resize(next.len(), position);
+        }
+
+        if !unlimited {
+            let bytes = previous_bytes + next.capacity() * mem::size_of::<T>();
+            if let Err(limit) = limits.check(next.len(), bytes, position) {
+                return Err(Error::Limit(LimitExceeded { generation, limit }))
+            }
+        }
     }
+    Ok(())
 }
 
-impl<T, F> Iterator for LSystem<T, F> where T: Clone, F: FnMut(T) -> Vec<T> {
+/// The smallest number of symbols handed to a single task by `LSystem::par_step`, below which the
+/// cost of scheduling outweighs any gain from parallelism.
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_CHUNK: usize = 1024;
+
+#[cfg(feature = "parallel")]
+impl<T, R> LSystem<T, R> where T: Send, R: Rewrite<T> + Sync, for<'r> &'r R: Rewrite<T> {
+    /// Rewrite the current generation's contents in parallel, and return the result by
+    /// reference.
+    ///
+    /// The current generation is split into chunks which are rewritten on the `rayon` thread
+    /// pool, and the results are concatenated in order, so the new generation is identical to the
+    /// one `step` would produce. This requires rules that can be shared between threads: a
+    /// `Fn + Sync` closure rather than an `FnMut`, a `Rules` table, or either of those with
+    /// decomposition rules that are themselves `Fn + Sync`. A closure written directly in the call
+    /// to `LSystem::new` is inferred to be only `FnMut`, so bind it to a variable first, as below.
+    ///
+    /// This method is only available with the `parallel` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the L-system has `Limits` and one of them is exceeded. Use `try_par_step` to
+    /// handle this case instead.
+    ///
+    /// ```rust
+    /// use lsystem::LSystem;
+    ///
+    /// let rules = |x| match x {
+    ///     'F' => "FF-[-F+F+F]+[+F-F-F]".chars().collect(),
+    ///     c   => vec!(c)
+    /// };
+    /// let mut sequential = LSystem::new(vec!('F'), rules);
+    /// let mut parallel = LSystem::new(vec!('F'), rules);
+    ///
+    /// for _ in 0..5 {
+    ///     assert_eq!(sequential.step(), parallel.par_step());
+    /// }
+    /// ```
+    pub fn par_step(&mut self) -> &[T] {
+        let old_axiom = mem::take(&mut self.axiom);
+        let bytes = old_axiom.capacity() * mem::size_of::<T>();
+        let chunk = parallel_chunk(old_axiom.len());
+
+        if let Err(e) = self.par_rewrite(old_axiom.into_par_iter().chunks(chunk), chunk, bytes) {
+            panic!("{}", e);
+        }
+        &self.axiom
+    }
+
+    /// Rewrite the current generation in parallel like `par_step`, unless doing so would exceed
+    /// one of the L-system's `Limits`. In that case the current generation is left as it was, and
+    /// a limit that was exceeded is returned.
+    ///
+    /// The limits are checked in the same way as by `try_step`, as each chunk is rewritten. When
+    /// several chunks exceed a limit at once, it is not specified which of them is reported.
+    ///
+    /// This method is only available with the `parallel` feature.
+    ///
+    /// ```rust
+    /// use lsystem::LSystem;
+    /// use lsystem::limits::{Limit, Limits};
+    ///
+    /// let rules = |_: char| vec!('F'; 20);
+    /// let mut runaway = LSystem::new(vec!('F'), rules)
+    ///     .with_limits(Limits::new().max_length(1_000_000));
+    ///
+    /// let error = loop {
+    ///     if let Err(error) = runaway.try_par_step() {
+    ///         break error
+    ///     }
+    /// };
+    /// assert_eq!((error.generation, error.limit), (5, Limit::Length(1_000_000)));
+    /// assert_eq!(runaway.current().len(), 160_000);
+    /// ```
+    pub fn try_par_step(&mut self) -> Result<&[T], LimitExceeded> where T: Clone + Sync {
+        if self.limits.is_unlimited() {
+            return Ok(self.par_step())
+        }
+
+        // The old generation must survive a failed step, so rewrite copies of its symbols.
+        let old_axiom = mem::take(&mut self.axiom);
+        let bytes = old_axiom.capacity() * mem::size_of::<T>();
+        let chunk = parallel_chunk(old_axiom.len());
+
+        let chunks = old_axiom.par_chunks(chunk).map(|symbols| symbols.iter().cloned());
+        match self.par_rewrite(chunks, chunk, bytes) {
+            Ok(()) => Ok(&self.axiom),
+            Err(e) => {
+                self.axiom = old_axiom;
+                Err(e)
+            }
+        }
+    }
+
+    /// Build the next generation from chunks of `chunk` symbols of the previous one, which still
+    /// occupies `previous_bytes` of storage, in parallel. The limits are checked as each chunk
+    /// grows, counting the symbols and storage of every chunk.
+    fn par_rewrite<P, C>(&mut self, chunks: P, chunk: usize, previous_bytes: usize)
+                         -> Result<(), LimitExceeded>
+        where P: IndexedParallelIterator<Item = C>, C: IntoIterator<Item = T> {
+        let unlimited = self.limits.is_unlimited();
+        let recording = self.history.is_recording();
+        let (rules, limits, generation) = (&self.rules, &self.limits, self.generation + 1);
+
+        // The symbols and storage of the new generation so far, summed over every chunk.
+        let length = AtomicUsize::new(0);
+        let bytes = AtomicUsize::new(0);
+
+        // Each task also works out the parents of the symbols it produces, if they are needed.
+        let pieces: Vec<(Vec<T>, Vec<usize>)> = chunks.enumerate().map(|(n, symbols)| {
+            let mut rules = rules;
+            let mut piece = Vec::new();
+            let mut parents = Vec::new();
+            let (mut counted_length, mut counted_bytes) = (0, 0);
+
+            for (index, element) in symbols.into_iter().enumerate() {
+                piece.extend(rules.rewrite(element));
+                if recording {
+                    parents.resize(piece.len(), n * chunk + index);
+                }
+
+                if !unlimited {
+                    let added = piece.len() - counted_length;
+                    let allocated = piece.capacity() * mem::size_of::<T>() - counted_bytes;
+                    counted_length = piece.len();
+                    counted_bytes += allocated;
+
+                    let total = length.fetch_add(added, Ordering::Relaxed) + added;
+                    let total_bytes = bytes.fetch_add(allocated, Ordering::Relaxed) + allocated;
+                    if let Err(limit) = limits.check(total, previous_bytes + total_bytes,
+                                                     n * chunk + index) {
+                        return Err(LimitExceeded { generation, limit })
+                    }
+                }
+            }
+            Ok((piece, parents))
+        }).collect::<Result<_, _>>()?;
+
+        self.axiom.reserve_exact(pieces.iter().map(|(piece, _)| piece.len()).sum());
+        let mut all_parents = Vec::new();
+        for (piece, parents) in pieces {
+            self.axiom.extend(piece);
+            all_parents.extend(parents);
+        }
+        self.finish(all_parents);
+        Ok(())
+    }
+}
+
+/// The number of symbols handed to each task when rewriting a generation of `length` symbols in
+/// parallel.
+#[cfg(feature = "parallel")]
+fn parallel_chunk(length: usize) -> usize {
+    (length / (rayon::current_num_threads() * 4)).max(MIN_PARALLEL_CHUNK)
+}
+
+impl<T, R> Iterator for LSystem<T, R> where T: Clone, R: Rewrite<T> {
     type Item = Vec<T>;
 
     /// Yield the next iteration of the L-system by rewriting the current axiom's contents using
     /// the production rules.
     fn next(&mut self) -> Option<Vec<T>> {
-        // In order to ensure that the "n = 0" case returns the original axiom, store whether we
-        // are in this state or not.
-        if self.zeroth {
-            self.zeroth = false;
-            return Some(self.axiom.clone())
-        }
-
-        // Otherwise, apply the production rules to the axiom to produce a new axiom for the
-        // iteration level.
-        let old_axiom = mem::replace(&mut self.axiom, Vec::new());
-
-        for element in old_axiom.into_iter() {
-            self.axiom.extend((self.rules)(element).into_iter());
+        // In order to ensure that the "n = 0" case returns the original axiom, keep track of
+        // whether the current generation has been yielded yet. Only once it has do we need to
+        // apply the production rules to produce the next one. If that would exceed the limits of
+        // the L-system, the iterator is exhausted instead.
+        if !self.fresh && self.try_step().is_err() {
+            return None
         }
+        self.fresh = false;
         Some(self.axiom.clone())
     }
 }
@@ -126,6 +654,8 @@ pub mod turtle {
         Forward(u32), Left(f32), Right(f32), Push, Pop, Dummy
     }
 
+    /// How a symbol is drawn. For enums, this can be derived from attributes on the variants with
+    /// `#[derive(TurtleInterpretation)]` from the companion `lsystem-derive` crate.
     pub trait TurtleInterpretation {
         fn to_turtle(&self) -> Turtle;
     }
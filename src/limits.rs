@@ -0,0 +1,144 @@
+//! Limits on the size of, and time spent on, a derivation.
+//!
+//! Generations usually grow exponentially, so a small change to a production can turn an
+//! L-system that comfortably reaches its twelfth generation into one that exhausts all available
+//! memory trying. Giving an `LSystem` a set of `Limits` makes it stop rewriting as soon as one of
+//! them is exceeded, and report which one it was.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//! use lsystem::limits::{Limit, Limits};
+//!
+//! // Every generation of this system is twenty times as long as the last.
+//! let mut runaway = LSystem::new(vec!('F'), |_| vec!('F'; 20))
+//!     .with_limits(Limits::new().max_length(1_000_000));
+//!
+//! let error = loop {
+//!     if let Err(error) = runaway.try_step() {
+//!         break error
+//!     }
+//! };
+//! assert_eq!(error.generation, 5);
+//! assert_eq!(error.limit, Limit::Length(1_000_000));
+//!
+//! // The last generation within the limits is kept, and iteration stops.
+//! assert_eq!(runaway.generation(), 4);
+//! assert_eq!(runaway.current().len(), 160_000);
+//! assert_eq!(runaway.nth(12), None);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How many symbols to rewrite between checks of the deadline, since reading the clock is
+/// relatively slow.
+const DEADLINE_INTERVAL: usize = 4096;
+
+/// A set of limits on the derivation of an L-system. By default, nothing is limited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    max_length: Option<usize>,
+    max_allocation: Option<usize>,
+    deadline: Option<Instant>
+}
+
+impl Limits {
+    /// Creates a new set of limits, which does not limit anything.
+    pub fn new() -> Limits {
+        Limits::default()
+    }
+
+    /// Limit the number of symbols in any generation.
+    pub fn max_length(mut self, symbols: usize) -> Limits {
+        self.max_length = Some(symbols);
+        self
+    }
+
+    /// Limit the number of bytes used to hold symbols at any one time. While rewriting, this
+    /// includes the storage for both the old and the new generation.
+    pub fn max_allocation(mut self, bytes: usize) -> Limits {
+        self.max_allocation = Some(bytes);
+        self
+    }
+
+    /// Stop rewriting once the given moment has passed.
+    pub fn deadline(mut self, deadline: Instant) -> Limits {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop rewriting once the given amount of time has passed from now.
+    pub fn timeout(self, timeout: Duration) -> Limits {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Whether these limits do not restrict anything.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Limits::default()
+    }
+
+    /// Check a generation that is being built, which currently holds `length` symbols in `bytes`
+    /// of storage (including any storage still used by the previous generation), after
+    /// `rewritten` symbols of the previous generation have been rewritten.
+    pub(crate) fn check(&self, length: usize, bytes: usize, rewritten: usize) -> Result<(), Limit> {
+        if let Some(max) = self.max_length {
+            if length > max {
+                return Err(Limit::Length(max))
+            }
+        }
+
+        if let Some(max) = self.max_allocation {
+            if bytes > max {
+                return Err(Limit::Allocation(max))
+            }
+        }
+
+        if let Some(deadline) = self.deadline {
+            if rewritten.is_multiple_of(DEADLINE_INTERVAL) && Instant::now() > deadline {
+                return Err(Limit::Deadline(deadline))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One of the limits in a set of `Limits`, along with its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// The maximum number of symbols in a generation.
+    Length(usize),
+    /// The maximum number of bytes used to hold symbols.
+    Allocation(usize),
+    /// The moment after which rewriting stops.
+    Deadline(Instant)
+}
+
+/// The error produced when a derivation exceeds one of its limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LimitExceeded {
+    /// The generation that was being produced when the limit was exceeded.
+    pub generation: usize,
+    /// The limit that was exceeded.
+    pub limit: Limit
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.limit {
+            Limit::Length(max) => {
+                write!(f, "generation {} is longer than {} symbols", self.generation, max)
+            },
+            Limit::Allocation(max) => {
+                write!(f, "generation {} needs more than {} bytes", self.generation, max)
+            },
+            Limit::Deadline(_) => {
+                write!(f, "deadline passed while producing generation {}", self.generation)
+            }
+        }
+    }
+}
+
+impl Error for LimitExceeded {}
@@ -0,0 +1,93 @@
+//! Memoised expansion of deterministic L-systems.
+//!
+//! In a deterministic, context-free L-system, a symbol always expands to the same string after
+//! the same number of steps. Deriving a generation by rewriting every symbol of the previous one
+//! repeats this work enormously: in the Sierpinski triangle, for example, the expansion of `A`
+//! to depth ten is computed anew for every `A` ten generations back. The `Memo` type instead
+//! caches the expansion of every `(symbol, depth)` pair it computes, and builds longer expansions
+//! by sharing shorter, cached ones rather than copying them. The production rules are applied
+//! only once per symbol.
+//!
+//! Expansions are held as the nodes of `compressed::CompressedGeneration`s, and every generation
+//! a `Memo` builds shares those nodes. Each new depth adds at most one node per symbol of the
+//! alphabet, however long the generation is, so generations of billions of symbols can be built,
+//! measured, searched and compared without ever being held as a flat string.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//!
+//! let rules = |x| match x {
+//!     'A' => vec!('B', '-', 'A', '-', 'B'),
+//!     'B' => vec!('A', '+', 'B', '+', 'A'),
+//!     c   => vec!(c)
+//! };
+//!
+//! let mut memo = LSystem::new(vec!('A'), rules).memoized();
+//! assert_eq!(memo.generation(8).to_vec(), LSystem::new(vec!('A'), rules).nth(8).unwrap());
+//! assert_eq!(memo.cached(), 32);
+//!
+//! // Generation 20 has almost 7 billion symbols, but reuses every expansion cached for
+//! // generation 8, so only four new expansions are needed for each extra step.
+//! let huge = memo.generation(20);
+//! assert_eq!(huge.len(), 6973568801);
+//! assert_eq!(memo.cached(), 80);
+//! assert_eq!(huge.slice(0..9).collect::<String>(), "A+B+A-B-A");
+//! ```
+
+use std::hash::Hash;
+
+use Rewrite;
+use compressed::{CompressedGeneration, Expansions};
+
+/// A memoising expander for a deterministic L-system.
+///
+/// This is usually created with `LSystem::memoized`.
+pub struct Memo<T, R> where R: Rewrite<T> {
+    axiom: Vec<T>,
+    rules: R,
+    expansions: Expansions<T>
+}
+
+impl<T, R> Memo<T, R> where T: Clone + Eq + Hash, R: Rewrite<T> {
+    /// Creates a new memoising expander with the given axiom and production rules.
+    pub fn new(axiom: Vec<T>, rules: R) -> Memo<T, R> {
+        Memo { axiom, rules, expansions: Expansions::new() }
+    }
+
+    /// The axiom from which generations are counted.
+    pub fn axiom(&self) -> &[T] {
+        &self.axiom
+    }
+
+    /// Build generation `n`, counting the axiom as generation zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the generation does not fit in a `u64`.
+    pub fn generation(&mut self, n: usize) -> CompressedGeneration<T> {
+        self.expansions.generation(&mut self.rules, &self.axiom, n)
+    }
+
+    /// The string that a single symbol expands to after `depth` steps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the expansion does not fit in a `u64`.
+    pub fn expansion(&mut self, symbol: &T, depth: usize) -> CompressedGeneration<T> {
+        self.expansions.expansion(&mut self.rules, symbol, depth)
+    }
+
+    /// The number of expansions currently held in the cache.
+    pub fn cached(&self) -> usize {
+        self.expansions.len()
+    }
+
+    /// Discard every cached expansion. Successors are kept, so the production rules are still
+    /// never applied to the same symbol twice. Generations that have already been built keep the
+    /// expansions they use.
+    pub fn clear(&mut self) {
+        self.expansions.clear();
+    }
+}
@@ -0,0 +1,235 @@
+//! A macro for writing L-systems in the notation of _The Algorithmic Beauty of Plants_.
+//!
+//! Spelling out each successor as a `vec!` of enum variants quickly becomes hard to read, and
+//! harder still to check against the string it is meant to represent. The `lsystem!` macro
+//! accepts the axiom and productions in something close to the notation of the book instead,
+//! along with a mapping from the symbols of that notation to the variants of an enum.
+//!
+//! The enum can be one of your own, named with `symbols`, in which case the macro is an
+//! expression. Or the macro can generate a new one, declared with `enum`, in which case the macro
+//! is a statement that binds the L-system with `let`. The enum is then declared beside that
+//! binding, so its variants can be named afterwards.
+//!
+//! Each entry of the mapping is either a variant, which is written as itself; a variant followed
+//! by the single token it is written as, such as `Plus = +`; or a pair of variants in brackets,
+//! such as `[Push, Pop]`, which start and end branches written as `[...]`. Using a symbol that is
+//! not in the mapping is a compile error, as is giving a symbol more than one production, and
+//! symbols without a production are constants.
+//!
+//! Since the grammar is made of Rust tokens, symbols must be separate tokens, so `F F` cannot be
+//! written `FF`. Most punctuation repeats freely, and `++` and `--` are both fine, but Rust reads
+//! some pairs as a single token: `&&`, `||`, `->`, `<=`, `>=`, `==`, `!=`, `=>`, `<<`, `>>`, `..`
+//! and `::`, as well as `+=` and the other compound assignments. Two symbols such as `&` and `&`
+//! must then be written with a space between them, as `& &`. A backslash is not a Rust token at
+//! all, so `\` cannot be used as a symbol. Where the book's notation runs into these, map the
+//! symbol to another token in the `symbols` mapping, such as `RollLeft = <` for the book's `\`,
+//! or write the L-system as text for `grammar::parse`, which accepts any character as a symbol.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! #[macro_use]
+//! extern crate lsystem;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Plant { X, F, Plus, Minus, Push, Pop }
+//!
+//! # fn main() {
+//! let mut plant = lsystem! {
+//!     symbols Plant { X, F, Plus = +, Minus = -, [Push, Pop] }
+//!     axiom: X;
+//!     X -> F + [[X] - X] - F [- F X] + X;
+//!     F -> F F;
+//! };
+//!
+//! use Plant::*;
+//! assert_eq!(plant.nth(1).unwrap(),
+//!            vec!(F, Plus, Push, Push, X, Pop, Minus, X, Pop, Minus, F, Push, Minus, F, X, Pop,
+//!                 Plus, X));
+//!
+//! // Or let the macro define the enum. It derives `Clone`, `Copy`, `Debug`, `PartialEq`, `Eq`
+//! // and `Hash`.
+//! lsystem::lsystem! {
+//!     let mut algae = enum Algae { A, B }
+//!     axiom: B;
+//!     A -> A B;
+//!     B -> A;
+//! }
+//! assert_eq!(algae.nth(4).unwrap(), vec!(Algae::A, Algae::B, Algae::A, Algae::A, Algae::B));
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! # #[macro_use]
+//! # extern crate lsystem;
+//! # fn main() {
+//! lsystem! {
+//!     let algae = enum Algae { A, B }
+//!     axiom: B;
+//!     A -> A B;
+//!     // Error: `A` already has a production.
+//!     A -> B;
+//! }
+//! # }
+//! ```
+
+/// Define an `LSystem` in the notation of _The Algorithmic Beauty of Plants_. See the `notation`
+/// module for details.
+#[macro_export]
+macro_rules! lsystem {
+    (symbols $ty:ident { $($mapping:tt)* } $($rest:tt)*) => (
+        $crate::lsystem!(@mapping ($) symbols $ty [] [] { $($mapping)* } $($rest)*)
+    );
+    (let $($binding:ident)+ = enum $ty:ident { $($mapping:tt)* } $($rest:tt)*) => (
+        $crate::lsystem!(@mapping ($) (let $($binding)+) $ty [] [] { $($mapping)* } $($rest)*)
+    );
+    (enum $($rest:tt)*) => (
+        compile_error!("a generated enum is declared with a statement such as \
+                        `lsystem! { let algae = enum Algae { A, B } axiom: ... }`")
+    );
+
+    // Turn the mapping into the arms of a macro that translates each symbol into its variant.
+    (@mapping ($d:tt) $kind:tt $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { , $($mapping:tt)* } $($rest:tt)*) => (
+        $crate::lsystem!(@mapping ($d) $kind $ty [$($arms)*] [$($variants)*] { $($mapping)* }
+                         $($rest)*)
+    );
+    (@mapping ($d:tt) $kind:tt $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { [$push:ident, $pop:ident] $($mapping:tt)* } $($rest:tt)*) => (
+        $crate::lsystem!(@mapping ($d) $kind $ty
+                         [$($arms)* (@push) => ($ty::$push); (@pop) => ($ty::$pop);]
+                         [$($variants)* $push $pop] { $($mapping)* } $($rest)*)
+    );
+    (@mapping ($d:tt) $kind:tt $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { $variant:ident = $symbol:tt $($mapping:tt)* } $($rest:tt)*) => (
+        $crate::lsystem!(@mapping ($d) $kind $ty [$($arms)* ($symbol) => ($ty::$variant);]
+                         [$($variants)* $variant] { $($mapping)* } $($rest)*)
+    );
+    (@mapping ($d:tt) $kind:tt $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { $variant:ident $($mapping:tt)* } $($rest:tt)*) => (
+        $crate::lsystem!(@mapping ($d) $kind $ty [$($arms)* ($variant) => ($ty::$variant);]
+                         [$($variants)* $variant] { $($mapping)* } $($rest)*)
+    );
+    (@mapping ($d:tt) symbols $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { } axiom : $($rest:tt)*) => (
+        $crate::lsystem!(@system ($d) [$($arms)*] $($rest)*)
+    );
+    // The generated enum is declared beside the binding, so that it can be named afterwards.
+    (@mapping ($d:tt) (let $($binding:ident)+) $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { } axiom : $($rest:tt)*) => (
+        #[allow(dead_code)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        enum $ty { $($variants),* }
+
+        let $($binding)+ = $crate::lsystem!(@system ($d) [$($arms)*] $($rest)*);
+    );
+    (@mapping ($d:tt) $kind:tt $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { } $($rest:tt)*) => (
+        compile_error!("expected `axiom:` after the symbols")
+    );
+    (@mapping ($d:tt) $kind:tt $ty:ident [$($arms:tt)*] [$($variants:ident)*]
+     { $unexpected:tt $($mapping:tt)* } $($rest:tt)*) => (
+        compile_error!(concat!("unexpected `", stringify!($unexpected), "` in the symbols"))
+    );
+
+    (@system ($d:tt) [$($arms:tt)*] $($rest:tt)*) => ({
+        macro_rules! lsystem_symbol {
+            $($arms)*
+            (@push) => (compile_error!("branches need a mapping such as `[Push, Pop]`"));
+            (@pop) => (compile_error!("branches need a mapping such as `[Push, Pop]`"));
+            ($d symbol:tt) => (
+                compile_error!(concat!("unknown symbol `", stringify!($d symbol), "`"))
+            );
+        }
+
+        $crate::lsystem!(@split (@rules ($d) []) [] $($rest)*)
+    });
+
+    // Collect the tokens up to the next `;`, then continue with `$then`. Several tokens are
+    // collected at a time, which keeps the recursion shallow enough for long productions.
+    (@split ($($then:tt)*) [$($acc:tt)*] ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)*] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*] $t1:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*] $t1:tt $t2:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1 $t2] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*] $t1:tt $t2:tt $t3:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1 $t2 $t3] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1 $t2 $t3 $t4] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*] $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1 $t2 $t3 $t4 $t5] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*]
+     $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1 $t2 $t3 $t4 $t5 $t6] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*]
+     $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt ; $($rest:tt)*) => (
+        $crate::lsystem!($($then)* [$($acc)* $t1 $t2 $t3 $t4 $t5 $t6 $t7] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*]
+     $t1:tt $t2:tt $t3:tt $t4:tt $t5:tt $t6:tt $t7:tt $t8:tt $($rest:tt)*) => (
+        $crate::lsystem!(@split ($($then)*) [$($acc)* $t1 $t2 $t3 $t4 $t5 $t6 $t7 $t8] $($rest)*)
+    );
+    (@split ($($then:tt)*) [$($acc:tt)*] $($rest:tt)*) => (
+        compile_error!("expected `;` at the end of the axiom or production")
+    );
+
+    // Collect the productions, once the axiom is known.
+    (@rules ($d:tt) [$(($predecessor:tt [$($successor:tt)*]))*] [$($axiom:tt)*]) => (
+        $crate::LSystem::new({
+            let mut axiom = Vec::new();
+            $crate::lsystem!(@string axiom $($axiom)*);
+            axiom
+        }, |symbol| match symbol {
+            $(lsystem_symbol!($predecessor) => {
+                let mut successor = Vec::new();
+                $crate::lsystem!(@string successor $($successor)*);
+                successor
+            },)*
+            #[allow(unreachable_patterns)]
+            symbol => vec!(symbol)
+        })
+    );
+    (@rules ($d:tt) [$($productions:tt)*] [$($axiom:tt)*] $predecessor:tt -> $($rest:tt)*) => (
+        $crate::lsystem!(@split (@production ($d) [$($productions)*] [$($axiom)*] $predecessor) []
+                         $($rest)*)
+    );
+    (@rules ($d:tt) [$($productions:tt)*] [$($axiom:tt)*] $($rest:tt)*) => (
+        compile_error!("expected a production such as `A -> A B;`")
+    );
+    // Check that the new predecessor has no production yet, then add its production.
+    (@production ($d:tt) [$(($existing:tt [$($done:tt)*]))*] [$($axiom:tt)*] $predecessor:tt
+     [$($successor:tt)*] $($rest:tt)*) => ({
+        macro_rules! lsystem_duplicate {
+            ($predecessor) => (
+                compile_error!(concat!("`", stringify!($predecessor),
+                                       "` already has a production"))
+            );
+            ($d other:tt) => ();
+        }
+        $(lsystem_duplicate!($existing);)*
+
+        $crate::lsystem!(@rules ($d) [$(($existing [$($done)*]))*
+                                      ($predecessor [$($successor)*])] [$($axiom)*] $($rest)*)
+    });
+
+    // Push the variant of each symbol onto `$string`, turning brackets into branches.
+    (@string $string:ident $($symbol:tt)*) => (
+        $($crate::lsystem!(@symbol $string $symbol);)*
+    );
+    (@symbol $string:ident [$($branch:tt)*]) => ({
+        $string.push(lsystem_symbol!(@push));
+        $crate::lsystem!(@string $string $($branch)*);
+        $string.push(lsystem_symbol!(@pop));
+    });
+    (@symbol $string:ident $symbol:tt) => (
+        $string.push(lsystem_symbol!($symbol))
+    );
+}
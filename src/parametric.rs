@@ -0,0 +1,725 @@
+//! Parametric L-systems, in which each symbol carries numeric parameters and productions are
+//! selected by guard conditions on those parameters.
+//!
+//! It is possible to write a parametric L-system with `LSystem` alone, by giving the variants of
+//! an `enum` some fields and matching on them in the rule closure. But the productions are then
+//! opaque code. This module instead represents them as data: a `Production` is made of a
+//! `Pattern` that names the parameters of its predecessor, an optional guard `Expr`, and a list of
+//! `Template`s whose argument expressions compute the parameters of each successor module.
+//! Productions can therefore be printed, compared, inspected and validated before any rewriting
+//! takes place.
+//!
+//! ## An Example
+//!
+//! The following is example 1.7 of _The Algorithmic Beauty of Plants_:
+//!
+//! ```rust
+//! use lsystem::parametric::{Module, ParametricLSystem, Pattern, Production, Template, num, param};
+//!
+//! let x = || param("x");
+//! let y = || param("y");
+//!
+//! let productions = vec!(
+//!     Production::new(Pattern::new('A', &["x", "y"]))
+//!         .guard(y().less_eq(3.0))
+//!         .successor(vec!(Template::new('A', vec!(x() * 2.0, x() + y())))),
+//!     Production::new(Pattern::new('A', &["x", "y"]))
+//!         .guard(y().greater(3.0))
+//!         .successor(vec!(Template::new('B', vec!(x())),
+//!                         Template::new('A', vec!(x() / y(), num(0.0))))),
+//!     Production::new(Pattern::new('B', &["x"]))
+//!         .guard(x().less(1.0))
+//!         .successor(vec!(Template::new('C', vec!()))),
+//!     Production::new(Pattern::new('B', &["x"]))
+//!         .guard(x().greater_eq(1.0))
+//!         .successor(vec!(Template::new('B', vec!(x() - 1.0)))));
+//!
+//! assert_eq!(productions[0].to_string(), "A(x, y) : y <= 3 -> A(x * 2, x + y)");
+//!
+//! let axiom = vec!(Module::new('B', vec!(2.0)), Module::new('A', vec!(4.0, 4.0)));
+//! let mut system = ParametricLSystem::new(axiom, productions);
+//!
+//! assert_eq!(system.nth(1).unwrap(), vec!(Module::new('B', vec!(1.0)),
+//!                                         Module::new('B', vec!(4.0)),
+//!                                         Module::new('A', vec!(1.0, 0.0))));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A symbol together with its actual parameter values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module<S> {
+    /// The symbol of the module.
+    pub symbol: S,
+    /// The parameter values of the module, in order.
+    pub params: Vec<f64>
+}
+
+impl<S> Module<S> {
+    /// Creates a new module with the given symbol and parameter values.
+    pub fn new(symbol: S, params: Vec<f64>) -> Module<S> {
+        Module { symbol, params }
+    }
+}
+
+impl<S> fmt::Display for Module<S> where S: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if !self.params.is_empty() {
+            write!(f, "(")?;
+            for (index, value) in self.params.iter().enumerate() {
+                if index > 0 { write!(f, ", ")?; }
+                write!(f, "{}", value)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Unary operators of the expression language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg, Not
+}
+
+/// Binary operators of the expression language, from the lowest to the highest precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or, And,
+    Eq, Ne, Lt, Le, Gt, Ge,
+    Add, Sub,
+    Mul, Div,
+    Pow
+}
+
+impl BinaryOp {
+    pub(crate) fn symbol(&self) -> &'static str {
+        match *self {
+            BinaryOp::Or  => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::Eq  => "==",
+            BinaryOp::Ne  => "!=",
+            BinaryOp::Lt  => "<",
+            BinaryOp::Le  => "<=",
+            BinaryOp::Gt  => ">",
+            BinaryOp::Ge  => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Pow => "^"
+        }
+    }
+
+    pub(crate) fn precedence(&self) -> u8 {
+        match *self {
+            BinaryOp::Or  => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Lt |
+            BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 3,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Mul | BinaryOp::Div => 5,
+            BinaryOp::Pow => 7
+        }
+    }
+
+    fn apply(&self, lhs: f64, rhs: f64) -> f64 {
+        fn truth(value: bool) -> f64 { if value { 1.0 } else { 0.0 } }
+
+        match *self {
+            BinaryOp::Or  => truth(is_true(lhs) || is_true(rhs)),
+            BinaryOp::And => truth(is_true(lhs) && is_true(rhs)),
+            BinaryOp::Eq  => truth(lhs == rhs),
+            BinaryOp::Ne  => truth(lhs != rhs),
+            BinaryOp::Lt  => truth(lhs < rhs),
+            BinaryOp::Le  => truth(lhs <= rhs),
+            BinaryOp::Gt  => truth(lhs > rhs),
+            BinaryOp::Ge  => truth(lhs >= rhs),
+            BinaryOp::Add => lhs + rhs,
+            BinaryOp::Sub => lhs - rhs,
+            BinaryOp::Mul => lhs * rhs,
+            BinaryOp::Div => lhs / rhs,
+            BinaryOp::Pow => lhs.powf(rhs)
+        }
+    }
+}
+
+/// Functions of the expression language. Angles are in radians.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    Abs, Sqrt, Exp, Ln, Log10,
+    Floor, Ceil, Round,
+    Sin, Cos, Tan, Asin, Acos, Atan, Atan2,
+    Min, Max
+}
+
+/// Every function, for looking them up by name.
+const FUNCTIONS: [Function; 17] = [
+    Function::Abs, Function::Sqrt, Function::Exp, Function::Ln, Function::Log10,
+    Function::Floor, Function::Ceil, Function::Round,
+    Function::Sin, Function::Cos, Function::Tan, Function::Asin, Function::Acos, Function::Atan,
+    Function::Atan2,
+    Function::Min, Function::Max
+];
+
+impl Function {
+    /// The function with the given name, if there is one.
+    pub fn from_name(name: &str) -> Option<Function> {
+        FUNCTIONS.iter().cloned().find(|function| function.name() == name)
+    }
+
+    /// The name of the function, as it is written in expressions.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Function::Abs   => "abs",
+            Function::Sqrt  => "sqrt",
+            Function::Exp   => "exp",
+            Function::Ln    => "ln",
+            Function::Log10 => "log10",
+            Function::Floor => "floor",
+            Function::Ceil  => "ceil",
+            Function::Round => "round",
+            Function::Sin   => "sin",
+            Function::Cos   => "cos",
+            Function::Tan   => "tan",
+            Function::Asin  => "asin",
+            Function::Acos  => "acos",
+            Function::Atan  => "atan",
+            Function::Atan2 => "atan2",
+            Function::Min   => "min",
+            Function::Max   => "max"
+        }
+    }
+
+    /// The number of arguments the function takes.
+    pub fn arity(&self) -> usize {
+        match *self {
+            Function::Atan2 | Function::Min | Function::Max => 2,
+            _ => 1
+        }
+    }
+
+    fn apply(&self, args: &[f64]) -> f64 {
+        match *self {
+            Function::Abs   => args[0].abs(),
+            Function::Sqrt  => args[0].sqrt(),
+            Function::Exp   => args[0].exp(),
+            Function::Ln    => args[0].ln(),
+            Function::Log10 => args[0].log10(),
+            Function::Floor => args[0].floor(),
+            Function::Ceil  => args[0].ceil(),
+            Function::Round => args[0].round(),
+            Function::Sin   => args[0].sin(),
+            Function::Cos   => args[0].cos(),
+            Function::Tan   => args[0].tan(),
+            Function::Asin  => args[0].asin(),
+            Function::Acos  => args[0].acos(),
+            Function::Atan  => args[0].atan(),
+            Function::Atan2 => args[0].atan2(args[1]),
+            Function::Min   => args[0].min(args[1]),
+            Function::Max   => args[0].max(args[1])
+        }
+    }
+}
+
+/// Whether a value counts as true when used as a condition: anything other than zero or `NaN`.
+fn is_true(value: f64) -> bool {
+    value != 0.0 && !value.is_nan()
+}
+
+/// An arithmetic or logical expression over the formal parameters of a production.
+///
+/// All values are `f64`. Comparisons and logical operators produce `1` for true and `0` for false,
+/// and any value other than zero (or `NaN`) is considered true.
+///
+/// Expressions are most easily built from `param` and `num` using the usual arithmetic operators
+/// along with the comparison methods defined here.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// A numeric constant.
+    Num(f64),
+    /// A reference to a formal parameter of the predecessor.
+    Param(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// A call of a function, with as many arguments as it takes.
+    Call(Function, Vec<Expr>)
+}
+
+/// An expression referring to the formal parameter with the given name.
+pub fn param(name: &str) -> Expr {
+    Expr::Param(name.to_string())
+}
+
+/// An expression with a constant value.
+pub fn num(value: f64) -> Expr {
+    Expr::Num(value)
+}
+
+impl Expr {
+    /// Combine two expressions with a binary operator.
+    pub fn binary<E: Into<Expr>>(self, op: BinaryOp, rhs: E) -> Expr {
+        Expr::Binary(op, Box::new(self), Box::new(rhs.into()))
+    }
+
+    /// An expression that is true when this one is less than `rhs`.
+    pub fn less<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Lt, rhs) }
+
+    /// An expression that is true when this one is at most `rhs`.
+    pub fn less_eq<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Le, rhs) }
+
+    /// An expression that is true when this one is greater than `rhs`.
+    pub fn greater<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Gt, rhs) }
+
+    /// An expression that is true when this one is at least `rhs`.
+    pub fn greater_eq<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Ge, rhs) }
+
+    /// An expression that is true when this one is equal to `rhs`.
+    pub fn equal<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Eq, rhs) }
+
+    /// An expression that is true when this one is not equal to `rhs`.
+    pub fn not_equal<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Ne, rhs) }
+
+    /// An expression that is true when both this one and `rhs` are.
+    pub fn and<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::And, rhs) }
+
+    /// An expression that is true when either this one or `rhs` is.
+    pub fn or<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Or, rhs) }
+
+    /// This expression raised to the power of `rhs`.
+    pub fn pow<E: Into<Expr>>(self, rhs: E) -> Expr { self.binary(BinaryOp::Pow, rhs) }
+
+    /// The logical negation of this expression.
+    pub fn negate(self) -> Expr {
+        Expr::Unary(UnaryOp::Not, Box::new(self))
+    }
+
+    /// A call of `function` with the given arguments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function takes a different number of arguments.
+    pub fn call(function: Function, args: Vec<Expr>) -> Expr {
+        if args.len() != function.arity() {
+            panic!("`{}` takes {} arguments, not {}", function.name(), function.arity(),
+                   args.len());
+        }
+        Expr::Call(function, args)
+    }
+
+    /// The names of all of the parameters referred to by this expression, in order of appearance.
+    pub fn params(&self) -> Vec<&str> {
+        let mut found = Vec::new();
+        self.collect_params(&mut found);
+        found
+    }
+
+    fn collect_params<'a>(&'a self, found: &mut Vec<&'a str>) {
+        match *self {
+            Expr::Num(_) => (),
+            Expr::Param(ref name) => found.push(name),
+            Expr::Unary(_, ref operand) => operand.collect_params(found),
+            Expr::Binary(_, ref lhs, ref rhs) => {
+                lhs.collect_params(found);
+                rhs.collect_params(found);
+            },
+            Expr::Call(_, ref args) => {
+                for arg in args.iter() {
+                    arg.collect_params(found);
+                }
+            }
+        }
+    }
+
+    /// Check that every function call in the expression has the right number of arguments.
+    fn check_calls(&self) -> Result<(), ValidationError> {
+        match *self {
+            Expr::Num(_) | Expr::Param(_) => Ok(()),
+            Expr::Unary(_, ref operand) => operand.check_calls(),
+            Expr::Binary(_, ref lhs, ref rhs) => {
+                lhs.check_calls()?;
+                rhs.check_calls()
+            },
+            Expr::Call(function, ref args) => {
+                if args.len() != function.arity() {
+                    return Err(ValidationError::WrongArgumentCount { function, found: args.len() })
+                }
+                args.iter().try_for_each(Expr::check_calls)
+            }
+        }
+    }
+
+    /// Evaluate the expression, binding each of the `names` to the value at the same position in
+    /// `values`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the expression refers to a parameter that is not among the `names`, or calls a
+    /// function with the wrong number of arguments. Productions that pass `Production::validate`
+    /// never do either.
+    pub fn eval(&self, names: &[String], values: &[f64]) -> f64 {
+        match *self {
+            Expr::Num(value) => value,
+            Expr::Param(ref name) => match names.iter().position(|n| n == name) {
+                Some(index) => values[index],
+                None => panic!("unbound parameter `{}`", name)
+            },
+            Expr::Unary(UnaryOp::Neg, ref operand) => -operand.eval(names, values),
+            Expr::Unary(UnaryOp::Not, ref operand) => {
+                if is_true(operand.eval(names, values)) { 0.0 } else { 1.0 }
+            },
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                op.apply(lhs.eval(names, values), rhs.eval(names, values))
+            },
+            Expr::Call(function, ref args) => {
+                let args: Vec<f64> = args.iter().map(|arg| arg.eval(names, values)).collect();
+                function.apply(&args)
+            }
+        }
+    }
+
+    /// The precedence of the outermost operator, used to decide where parentheses are needed.
+    /// The unary operators bind more tightly than `*` and `/`, but less tightly than `^`, and a
+    /// negative number is displayed as a negated one.
+    fn precedence(&self) -> u8 {
+        match *self {
+            Expr::Num(value) if value.is_finite() && value.is_sign_negative() => 6,
+            Expr::Num(_) | Expr::Param(_) | Expr::Call(..) => 8,
+            Expr::Unary(..) => 6,
+            Expr::Binary(op, _, _) => op.precedence()
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter, min: u8) -> fmt::Result {
+        if self.precedence() < min {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            // Infinities and `NaN` have no literal, so they are written as divisions by zero.
+            Expr::Num(value) if value.is_nan() => write!(f, "(0 / 0)"),
+            Expr::Num(value) if value.is_infinite() => write!(f, "({} / 0)", value.signum()),
+            Expr::Num(value) => write!(f, "{}", value),
+            Expr::Param(ref name) => write!(f, "{}", name),
+            Expr::Unary(UnaryOp::Neg, ref operand) => match **operand {
+                // `-2` is the negative number, so a negated number is parenthesised.
+                Expr::Num(value) if value.is_finite() => write!(f, "-({})", value),
+                _ => {
+                    write!(f, "-")?;
+                    operand.fmt_operand(f, 6)
+                }
+            },
+            Expr::Unary(UnaryOp::Not, ref operand) => {
+                write!(f, "!")?;
+                operand.fmt_operand(f, 6)
+            },
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                // Everything is left-associative except for exponentiation.
+                let prec = op.precedence();
+                let (left, right) = if op == BinaryOp::Pow { (prec + 1, prec) }
+                                    else { (prec, prec + 1) };
+                lhs.fmt_operand(f, left)?;
+                write!(f, " {} ", op.symbol())?;
+                rhs.fmt_operand(f, right)
+            },
+            Expr::Call(function, ref args) => {
+                write!(f, "{}(", function.name())?;
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 { write!(f, ", ")?; }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl From<f64> for Expr {
+    fn from(value: f64) -> Expr {
+        Expr::Num(value)
+    }
+}
+
+macro_rules! impl_expr_op {
+    ($Trait:ident, $method:ident, $op:expr) => (
+        impl<E: Into<Expr>> $Trait<E> for Expr {
+            type Output = Expr;
+
+            fn $method(self, rhs: E) -> Expr {
+                self.binary($op, rhs)
+            }
+        }
+    );
+}
+
+impl_expr_op!(Add, add, BinaryOp::Add);
+impl_expr_op!(Sub, sub, BinaryOp::Sub);
+impl_expr_op!(Mul, mul, BinaryOp::Mul);
+impl_expr_op!(Div, div, BinaryOp::Div);
+
+impl Neg for Expr {
+    type Output = Expr;
+
+    fn neg(self) -> Expr {
+        Expr::Unary(UnaryOp::Neg, Box::new(self))
+    }
+}
+
+/// The predecessor of a production: a symbol and the names of its formal parameters.
+///
+/// A pattern matches any module with the same symbol and the same number of parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern<S> {
+    pub symbol: S,
+    pub params: Vec<String>
+}
+
+impl<S> Pattern<S> {
+    /// Creates a new pattern with the given symbol and formal parameter names.
+    pub fn new(symbol: S, params: &[&str]) -> Pattern<S> {
+        Pattern { symbol, params: params.iter().map(|p| p.to_string()).collect() }
+    }
+
+    /// Whether this pattern matches the given module.
+    pub fn matches(&self, module: &Module<S>) -> bool where S: PartialEq {
+        self.symbol == module.symbol && self.params.len() == module.params.len()
+    }
+}
+
+impl<S> fmt::Display for Pattern<S> where S: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if !self.params.is_empty() {
+            write!(f, "({})", self.params.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A module in the successor of a production, whose parameter values are computed from the
+/// parameters of the predecessor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template<S> {
+    pub symbol: S,
+    pub args: Vec<Expr>
+}
+
+impl<S> Template<S> {
+    /// Creates a new template with the given symbol and argument expressions.
+    pub fn new(symbol: S, args: Vec<Expr>) -> Template<S> {
+        Template { symbol, args }
+    }
+}
+
+impl<S> fmt::Display for Template<S> where S: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if !self.args.is_empty() {
+            write!(f, "(")?;
+            for (index, arg) in self.args.iter().enumerate() {
+                if index > 0 { write!(f, ", ")?; }
+                write!(f, "{}", arg)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// A production of a parametric L-system, of the form `pred : guard -> succ`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Production<S> {
+    pub predecessor: Pattern<S>,
+    pub guard: Option<Expr>,
+    pub successor: Vec<Template<S>>
+}
+
+impl<S> Production<S> {
+    /// Creates a new production for the given predecessor, which has no guard and erases the
+    /// modules that it matches until a successor is supplied.
+    pub fn new(predecessor: Pattern<S>) -> Production<S> {
+        Production { predecessor, guard: None, successor: Vec::new() }
+    }
+
+    /// Sets the condition under which this production applies.
+    pub fn guard(mut self, guard: Expr) -> Production<S> {
+        self.guard = Some(guard);
+        self
+    }
+
+    /// Sets the modules that replace the predecessor.
+    pub fn successor(mut self, successor: Vec<Template<S>>) -> Production<S> {
+        self.successor = successor;
+        self
+    }
+
+    /// Check that the formal parameters of the predecessor are distinct, that the guard and
+    /// successor only refer to those parameters, and that they call every function with the right
+    /// number of arguments.
+    ///
+    /// ```rust
+    /// use lsystem::parametric::{Expr, Function, Pattern, Production, Template, ValidationError,
+    ///                           param};
+    ///
+    /// let production = Production::new(Pattern::new('A', &["x"]))
+    ///     .successor(vec!(Template::new('A', vec!(Expr::Call(Function::Max, vec!(param("x")))))));
+    /// assert_eq!(production.validate(),
+    ///            Err(ValidationError::WrongArgumentCount { function: Function::Max, found: 1 }));
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let names = &self.predecessor.params;
+
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) {
+                return Err(ValidationError::DuplicateParameter(name.clone()))
+            }
+        }
+
+        let exprs = self.guard.iter().chain(self.successor.iter().flat_map(|t| t.args.iter()));
+        for expr in exprs {
+            if let Some(name) = expr.params().into_iter().find(|p| !names.iter().any(|n| n == p)) {
+                return Err(ValidationError::UnboundParameter(name.to_string()))
+            }
+            expr.check_calls()?;
+        }
+        Ok(())
+    }
+
+    /// Apply this production to a module, returning its successor if the module matches the
+    /// predecessor and satisfies the guard.
+    pub fn apply(&self, module: &Module<S>) -> Option<Vec<Module<S>>> where S: Clone + PartialEq {
+        if !self.predecessor.matches(module) {
+            return None
+        }
+
+        let names = &self.predecessor.params;
+        if let Some(ref guard) = self.guard {
+            if !is_true(guard.eval(names, &module.params)) {
+                return None
+            }
+        }
+
+        Some(self.successor.iter().map(|template| {
+            let params = template.args.iter().map(|arg| arg.eval(names, &module.params)).collect();
+            Module::new(template.symbol.clone(), params)
+        }).collect())
+    }
+}
+
+impl<S> fmt::Display for Production<S> where S: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.predecessor)?;
+        if let Some(ref guard) = self.guard {
+            write!(f, " : {}", guard)?;
+        }
+        write!(f, " ->")?;
+        for template in self.successor.iter() {
+            write!(f, " {}", template)?;
+        }
+        Ok(())
+    }
+}
+
+/// The ways in which a production can be malformed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// A formal parameter name appears more than once in the predecessor.
+    DuplicateParameter(String),
+    /// The guard or successor refers to a name that is not a formal parameter of the predecessor.
+    UnboundParameter(String),
+    /// The guard or successor calls a function with the wrong number of arguments.
+    WrongArgumentCount {
+        /// The function.
+        function: Function,
+        /// The number of arguments it was given.
+        found: usize
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::DuplicateParameter(ref name) => {
+                write!(f, "parameter `{}` is declared more than once", name)
+            },
+            ValidationError::UnboundParameter(ref name) => {
+                write!(f, "parameter `{}` is not declared by the predecessor", name)
+            },
+            ValidationError::WrongArgumentCount { function, found } => {
+                write!(f, "`{}` takes {} arguments, not {}", function.name(), function.arity(),
+                       found)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Create a parametric Lindenmayer System defined by an axiom of modules and a list of
+/// productions.
+///
+/// Each module is rewritten by the first production, in order, whose predecessor matches it and
+/// whose guard holds. Modules without any applicable production are left unchanged. As with
+/// `LSystem`, the first item yielded by the iterator is the axiom itself, and the iterator is
+/// never exhausted.
+pub struct ParametricLSystem<S> {
+    axiom: Vec<Module<S>>,
+    productions: Vec<Production<S>>,
+    zeroth: bool
+}
+
+impl<S> ParametricLSystem<S> where S: fmt::Display {
+    /// Creates a new representation of a parametric L-system with the given axiom and
+    /// productions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the productions fails `Production::validate`, so that iteration itself
+    /// can never fail.
+    pub fn new(axiom: Vec<Module<S>>, productions: Vec<Production<S>>) -> ParametricLSystem<S> {
+        for production in productions.iter() {
+            if let Err(e) = production.validate() {
+                panic!("invalid production `{}`: {}", production, e);
+            }
+        }
+        ParametricLSystem { axiom, productions, zeroth: true }
+    }
+
+    /// The productions of this L-system, in the order they are tried.
+    pub fn productions(&self) -> &[Production<S>] {
+        &self.productions
+    }
+}
+
+impl<S> Iterator for ParametricLSystem<S> where S: Clone + PartialEq {
+    type Item = Vec<Module<S>>;
+
+    /// Yield the next iteration of the L-system by rewriting each module with the first applicable
+    /// production.
+    fn next(&mut self) -> Option<Vec<Module<S>>> {
+        if self.zeroth {
+            self.zeroth = false;
+            return Some(self.axiom.clone())
+        }
+
+        let old_axiom = mem::take(&mut self.axiom);
+
+        for module in old_axiom {
+            match self.productions.iter().filter_map(|p| p.apply(&module)).next() {
+                Some(successor) => self.axiom.extend(successor),
+                None => self.axiom.push(module)
+            }
+        }
+        Some(self.axiom.clone())
+    }
+}
@@ -0,0 +1,124 @@
+//! Decomposition and interpretation rules, applied alongside the production rules.
+//!
+//! Following L-studio, an L-system can have two further kinds of rules besides its productions.
+//! Decomposition rules break a symbol down into its parts, and are applied repeatedly within each
+//! step, immediately after the productions, until none of them apply. Interpretation rules map a
+//! symbol to the commands used to draw it, and are applied only when a generation is interpreted,
+//! so their results are never rewritten themselves.
+//!
+//! Both kinds of rule take a symbol by reference and return `None` when they leave it as it is.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//!
+//! let mut plant = LSystem::new(vec!('A'), |x| match x {
+//!     'A' => vec!('I', 'A'),
+//!     c   => vec!(c)
+//! }).with_decomposition(|x: &char| match *x {
+//!     // Every internode carries a leaf, which is itself made up of two leaflets.
+//!     'I' => Some("F[L]".chars().collect()),
+//!     'L' => Some("+l-l".chars().collect()),
+//!     _   => None
+//! });
+//!
+//! plant.step();
+//! plant.step();
+//! assert_eq!(plant.current().iter().collect::<String>(), "F[+l-l]F[+l-l]A");
+//!
+//! // The apex is not drawn, and leaflets are drawn as short lines.
+//! let drawing = plant.interpret(|x: &char| match *x {
+//!     'A' => Some(vec!()),
+//!     'l' => Some(vec!('f')),
+//!     _   => None
+//! });
+//! assert_eq!(drawing.into_iter().collect::<String>(), "F[+f-f]F[+f-f]");
+//! ```
+
+use Rewrite;
+
+/// How many times decomposition rules may be applied to the parts of a single symbol before the
+/// decomposition is assumed never to finish.
+const MAX_DECOMPOSITION_DEPTH: usize = 1024;
+
+/// Production rules followed by decomposition rules, which are applied to each symbol the
+/// productions produce until none of them apply.
+///
+/// This is usually created with `LSystem::with_decomposition`.
+///
+/// # Panics
+///
+/// Rewriting panics if decomposing a symbol does not finish within 1024 levels, which usually
+/// means that a decomposition rule (eventually) produces its own predecessor.
+pub struct Decompose<R, D> {
+    rules: R,
+    decomposition: D
+}
+
+impl<R, D> Decompose<R, D> {
+    /// Combines production rules with decomposition rules.
+    pub fn new(rules: R, decomposition: D) -> Decompose<R, D> {
+        Decompose { rules, decomposition }
+    }
+}
+
+impl<T, R, D> Rewrite<T> for Decompose<R, D>
+    where R: Rewrite<T>, D: FnMut(&T) -> Option<Vec<T>> {
+    type Output = Vec<T>;
+
+    fn rewrite(&mut self, symbol: T) -> Vec<T> {
+        decompose(self.rules.rewrite(symbol), &mut self.decomposition)
+    }
+}
+
+/// Borrowed rules can be shared between threads, as `LSystem::par_step` requires, so long as the
+/// decomposition rules are `Fn` rather than `FnMut`.
+impl<'a, T, R, D> Rewrite<T> for &'a Decompose<R, D>
+    where &'a R: Rewrite<T>, D: Fn(&T) -> Option<Vec<T>> {
+    type Output = Vec<T>;
+
+    fn rewrite(&mut self, symbol: T) -> Vec<T> {
+        let mut rules = &self.rules;
+        decompose(rules.rewrite(symbol), &self.decomposition)
+    }
+}
+
+/// Apply decomposition rules to the symbols produced by the productions, until none of them apply.
+fn decompose<T, I, D>(produced: I, mut decomposition: D) -> Vec<T>
+    where I: IntoIterator<Item = T>, D: FnMut(&T) -> Option<Vec<T>> {
+    let mut decomposed = Vec::new();
+
+    // Symbols still to be decomposed, in reverse order, with the number of decompositions that
+    // produced them.
+    let mut pending: Vec<(T, usize)> = produced.into_iter().map(|part| (part, 0)).collect();
+    pending.reverse();
+
+    while let Some((part, depth)) = pending.pop() {
+        match decomposition(&part) {
+            Some(parts) => {
+                if depth == MAX_DECOMPOSITION_DEPTH {
+                    panic!("decomposition did not finish after {} levels", depth);
+                }
+                pending.extend(parts.into_iter().rev().map(|part| (part, depth + 1)));
+            },
+            None => decomposed.push(part)
+        }
+    }
+    decomposed
+}
+
+/// Apply interpretation rules to each symbol of a string, keeping the symbols they do not apply
+/// to. The rules are applied only once, so their results are not interpreted again.
+pub fn interpret<T, H>(string: &[T], mut rules: H) -> Vec<T>
+    where T: Clone, H: FnMut(&T) -> Option<Vec<T>> {
+    let mut interpreted = Vec::with_capacity(string.len());
+
+    for symbol in string.iter() {
+        match rules(symbol) {
+            Some(commands) => interpreted.extend(commands),
+            None => interpreted.push(symbol.clone())
+        }
+    }
+    interpreted
+}
@@ -0,0 +1,101 @@
+//! Tracking which symbol of the previous generation produced each symbol of the current one.
+//!
+//! When visualising growth it is often useful to know where a symbol came from: colouring a
+//! plant by the branch of the axiom it grew from, say, or comparing two derivations symbol by
+//! symbol. An `LSystem` with provenance enabled records, for every symbol of each new generation,
+//! the index of its parent in the generation before. Recording the full `Lineage` keeps this
+//! information for every generation, so that any symbol can be traced back to the axiom.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//! use lsystem::provenance::Provenance;
+//!
+//! let mut algae = LSystem::new(vec!('B'), |x| match x {
+//!     'A' => vec!('A', 'B'),
+//!     _   => vec!('A')
+//! }).with_provenance(Provenance::Lineage);
+//!
+//! // B -> A -> AB -> ABA
+//! assert_eq!(algae.nth(3).unwrap(), vec!('A', 'B', 'A'));
+//! assert_eq!(algae.parents(), Some(&[0, 0, 1][..]));
+//!
+//! // The final A descends from the B of generation two, which descends from the only symbol of
+//! // each generation before it.
+//! assert_eq!(algae.lineage(2), Some(vec!(0, 0, 1)));
+//! ```
+
+/// How much provenance an `LSystem` should record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Provenance {
+    /// Record the parent of each symbol in the current generation only.
+    Parents,
+    /// Record the parents of each symbol in every generation, so that whole lineages can be
+    /// traced. This uses memory proportional to the total length of all generations.
+    Lineage
+}
+
+/// The provenance recorded so far by an `LSystem`.
+pub(crate) struct History {
+    mode: Option<Provenance>,
+    /// The parents of each generation since recording started, oldest first. Only the last is
+    /// kept when recording `Provenance::Parents`.
+    parents: Vec<Vec<usize>>
+}
+
+impl History {
+    pub(crate) fn new() -> History {
+        History { mode: None, parents: Vec::new() }
+    }
+
+    /// Start recording with the given mode, discarding anything recorded before.
+    pub(crate) fn start(&mut self, mode: Provenance) {
+        self.mode = Some(mode);
+        self.parents.clear();
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.mode.is_some()
+    }
+
+    /// Record the parents of a new generation.
+    pub(crate) fn record(&mut self, parents: Vec<usize>) {
+        match self.mode {
+            Some(Provenance::Parents) => {
+                self.parents.clear();
+                self.parents.push(parents);
+            },
+            Some(Provenance::Lineage) => self.parents.push(parents),
+            None => ()
+        }
+    }
+
+    /// The parents of the current generation, if any have been recorded.
+    pub(crate) fn parents(&self) -> Option<&[usize]> {
+        self.parents.last().map(|parents| &parents[..])
+    }
+
+    /// The ancestors of a symbol of the current generation, oldest first.
+    pub(crate) fn lineage(&self, index: usize) -> Option<Vec<usize>> {
+        if self.mode != Some(Provenance::Lineage) {
+            return None
+        }
+
+        if let Some(parents) = self.parents.last() {
+            if index >= parents.len() {
+                return None
+            }
+        }
+
+        let mut ancestors = Vec::with_capacity(self.parents.len());
+        let mut index = index;
+
+        for parents in self.parents.iter().rev() {
+            index = parents[index];
+            ancestors.push(index);
+        }
+        ancestors.reverse();
+        Some(ancestors)
+    }
+}
@@ -0,0 +1,110 @@
+//! Pruning of branches with cut symbols.
+//!
+//! _The Algorithmic Beauty of Plants_ describes a cut symbol, `%`, which removes itself and the
+//! remainder of the branch it appears in, up to (but not including) the bracket that closes the
+//! branch. Since every symbol of an `LSystem` is rewritten in isolation, no production can express
+//! this on its own. Instead, an `LSystem` can be given a `Pruning` pass, which applies cuts to
+//! every generation once it has been rewritten.
+//!
+//! Pruning is applied by `step`, `try_step`, `par_step` and iteration. The methods which expand
+//! each symbol on its own, namely `expand`, `growth`, `memoized` and `compressed`, cannot see the
+//! branch that a cut would remove, so they panic rather than return an unpruned result.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//! use lsystem::prune::Pruning;
+//!
+//! let mut plant = LSystem::new(vec!('A'), |x| match x {
+//!     'A' => "F[+B]A".chars().collect(),
+//!     // Lateral branches are cut back as soon as they have grown a single segment.
+//!     'B' => "F%F[-F]F".chars().collect(),
+//!     c   => vec!(c)
+//! }).with_pruning(Pruning::new('[', ']').cutting(vec!('%')));
+//!
+//! assert_eq!(plant.nth(2).unwrap().into_iter().collect::<String>(), "F[+F]F[+B]A");
+//! ```
+//!
+//! ```rust,should_panic
+//! # use lsystem::LSystem;
+//! # use lsystem::prune::Pruning;
+//! let mut cut = LSystem::new(vec!('B'), |x| match x {
+//!     'B' => "B[%C]D".chars().collect(),
+//!     c   => vec!(c)
+//! }).with_pruning(Pruning::new('[', ']').cutting(vec!('%')));
+//!
+//! // Panics: the expansion of `B` would keep the cut branch.
+//! cut.compressed(1);
+//! ```
+
+/// The symbols that delimit branches and cut them off.
+pub struct Pruning<T> {
+    push: T,
+    pop: T,
+    cuts: Vec<T>,
+    // Kept so that an `LSystem` can prune its generations without requiring `T: PartialEq`.
+    eq: fn(&T, &T) -> bool
+}
+
+impl<T> Pruning<T> where T: PartialEq {
+    /// Creates a new pruning pass from the symbols that start (`push`) and end (`pop`) a branch,
+    /// but with no cut symbols. These are added with `cutting`.
+    pub fn new(push: T, pop: T) -> Pruning<T> {
+        Pruning { push, pop, cuts: Vec::new(), eq: T::eq }
+    }
+}
+
+impl<T> Pruning<T> {
+    /// Adds symbols which cut off the remainder of their branch.
+    pub fn cutting(mut self, symbols: Vec<T>) -> Pruning<T> {
+        self.cuts.extend(symbols);
+        self
+    }
+
+    /// Remove every cut symbol from the string, along with the rest of the branch that follows it.
+    /// A cut outside of any branch removes the rest of the string.
+    pub fn prune(&self, string: &mut Vec<T>) {
+        let kept = self.kept(string);
+        retain(string, &kept);
+    }
+
+    /// Work out which symbols of the string survive pruning.
+    pub(crate) fn kept(&self, string: &[T]) -> Vec<bool> {
+        let eq = self.eq;
+        let mut kept = Vec::with_capacity(string.len());
+        let mut depth = 0usize;
+        // The depth of the branch currently being cut, if any.
+        let mut cutting = None;
+
+        for symbol in string.iter() {
+            let keep = if eq(symbol, &self.push) {
+                depth += 1;
+                cutting.is_none()
+            } else if eq(symbol, &self.pop) {
+                // The bracket that closes the branch being cut is kept, which ends the cut.
+                if cutting == Some(depth) {
+                    cutting = None;
+                }
+                depth = depth.saturating_sub(1);
+                cutting.is_none()
+            } else if cutting.is_none() && self.cuts.iter().any(|cut| eq(symbol, cut)) {
+                cutting = Some(depth);
+                false
+            } else {
+                cutting.is_none()
+            };
+            kept.push(keep);
+        }
+        kept
+    }
+}
+
+/// Keep only those elements of a vector whose entry in `kept` is true.
+pub(crate) fn retain<T>(elements: &mut Vec<T>, kept: &[bool]) {
+    let mut index = 0;
+    elements.retain(|_| {
+        index += 1;
+        kept[index - 1]
+    });
+}
@@ -0,0 +1,247 @@
+//! Production rules written as a table, rather than as a function.
+//!
+//! Most context-free L-systems rewrite a handful of symbols and leave every other symbol as it
+//! is. A `Rules` table lists a successor for each of the symbols that are rewritten, and treats
+//! every symbol it does not list as a constant. Unlike a closure, the table can be inspected:
+//! its productions can be listed, printed, and compared with those of another table.
+//!
+//! Tables can be built with the `rule` method, or with the `rules!` macro, which expands to the
+//! same thing.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! #[macro_use]
+//! extern crate lsystem;
+//!
+//! use lsystem::LSystem;
+//! use lsystem::rules::Rules;
+//!
+//! # fn main() {
+//! let algae = rules!('A' => ['A', 'B'], 'B' => ['A']);
+//! assert_eq!(algae, Rules::new().rule('B', vec!('A')).rule('A', vec!('A', 'B')));
+//! assert_eq!(algae.to_string(), "A -> A B\nB -> A");
+//!
+//! // Symbols without a production, such as C, are constants.
+//! let mut algae = LSystem::from_rules(vec!('B', 'C'), algae);
+//! assert_eq!(algae.nth(4).unwrap(), vec!('A', 'B', 'A', 'A', 'B', 'C'));
+//! # }
+//! ```
+
+use std::fmt;
+use std::mem;
+use std::slice;
+use std::sync::Arc;
+
+use Rewrite;
+use successor::Successor;
+
+/// A table of production rules, mapping each predecessor to its successor. Symbols without a
+/// production are rewritten into themselves.
+///
+/// Two tables are equal when they contain the same productions, in any order.
+///
+/// Successors are shared between the table, its clones, and the symbols it rewrites, so an
+/// `LSystem` that owns its table does not allocate for each symbol it rewrites.
+#[derive(Clone, Debug)]
+pub struct Rules<T> {
+    productions: Vec<(T, Arc<[T]>)>
+}
+
+impl<T> Rules<T> where T: PartialEq {
+    /// Creates an empty table, in which every symbol is a constant.
+    pub fn new() -> Rules<T> {
+        Rules { productions: Vec::new() }
+    }
+
+    /// Adds a production to the table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table already has a production for `predecessor`. Use `insert` to replace
+    /// an existing production instead.
+    pub fn rule(mut self, predecessor: T, successor: Vec<T>) -> Rules<T> {
+        if self.get(&predecessor).is_some() {
+            panic!("a symbol can only have one production");
+        }
+        self.productions.push((predecessor, Arc::from(successor)));
+        self
+    }
+
+    /// Adds a production to the table, returning the successor of the production it replaces, if
+    /// there was one.
+    pub fn insert(&mut self, predecessor: T, successor: Vec<T>) -> Option<Vec<T>> where T: Clone {
+        let successor = Arc::from(successor);
+        match self.productions.iter().position(|(p, _)| *p == predecessor) {
+            Some(index) => Some(mem::replace(&mut self.productions[index].1, successor).to_vec()),
+            None => {
+                self.productions.push((predecessor, successor));
+                None
+            }
+        }
+    }
+
+    /// The successor of `symbol`, or `None` if it is a constant.
+    pub fn get(&self, symbol: &T) -> Option<&[T]> {
+        self.shared(symbol).map(|successor| &successor[..])
+    }
+
+    fn shared(&self, symbol: &T) -> Option<&Arc<[T]>> {
+        self.productions.iter()
+            .find(|(predecessor, _)| predecessor == symbol)
+            .map(|(_, successor)| successor)
+    }
+}
+
+impl<T> Rules<T> {
+    /// The number of productions in the table.
+    pub fn len(&self) -> usize {
+        self.productions.len()
+    }
+
+    /// Whether the table has no productions at all.
+    pub fn is_empty(&self) -> bool {
+        self.productions.is_empty()
+    }
+
+    /// Iterate over the productions of the table as `(predecessor, successor)` pairs, in the
+    /// order they were added.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { productions: self.productions.iter() }
+    }
+}
+
+impl<T> Default for Rules<T> where T: PartialEq {
+    fn default() -> Rules<T> {
+        Rules::new()
+    }
+}
+
+impl<T> PartialEq for Rules<T> where T: PartialEq {
+    fn eq(&self, other: &Rules<T>) -> bool {
+        self.len() == other.len() &&
+            self.iter().all(|(predecessor, successor)| other.get(predecessor) == Some(successor))
+    }
+}
+
+impl<T> Eq for Rules<T> where T: Eq {}
+
+/// Each production is written on its own line, in the form `A -> A B`.
+impl<T> fmt::Display for Rules<T> where T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (predecessor, successor)) in self.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{} ->", predecessor)?;
+            for symbol in successor {
+                write!(f, " {}", symbol)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An owned table rewrites symbols without allocating, by sharing its successors.
+impl<T> Rewrite<T> for Rules<T> where T: Clone + PartialEq {
+    type Output = Rewritten<T>;
+
+    fn rewrite(&mut self, symbol: T) -> Rewritten<T> {
+        match self.shared(&symbol) {
+            Some(successor) => {
+                Rewritten { successor: Some(successor.clone()), next: 0, constant: None }
+            },
+            None => Rewritten { successor: None, next: 0, constant: Some(symbol) }
+        }
+    }
+}
+
+/// An iterator over the symbols that an owned `Rules` table rewrites a symbol into.
+pub struct Rewritten<T> {
+    /// The successor of the production that applied, shared with the table.
+    successor: Option<Arc<[T]>>,
+    /// The position in `successor` of the next symbol.
+    next: usize,
+    /// The symbol itself, if it is a constant and has not been yielded yet.
+    constant: Option<T>
+}
+
+impl<T> Iterator for Rewritten<T> where T: Clone {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.successor {
+            Some(ref successor) => {
+                let symbol = successor.get(self.next).cloned();
+                self.next += 1;
+                symbol
+            },
+            None => self.constant.take()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.successor {
+            Some(ref successor) => successor.len().saturating_sub(self.next),
+            None => self.constant.iter().len()
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+/// A borrowed table rewrites symbols without allocating, by borrowing its successors.
+impl<'a, T> Rewrite<T> for &'a Rules<T> where T: Clone + PartialEq {
+    type Output = Successor<'a, T>;
+
+    fn rewrite(&mut self, symbol: T) -> Successor<'a, T> {
+        let rules: &'a Rules<T> = self;
+        match rules.get(&symbol) {
+            Some(successor) => Successor::Slice(successor),
+            None => Successor::One(symbol)
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Rules<T> {
+    type Item = (&'a T, &'a [T]);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator over the productions of a `Rules` table.
+pub struct Iter<'a, T: 'a> {
+    productions: slice::Iter<'a, (T, Arc<[T]>)>
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (&'a T, &'a [T]);
+
+    fn next(&mut self) -> Option<(&'a T, &'a [T])> {
+        self.productions.next().map(|(predecessor, successor)| (predecessor, &successor[..]))
+    }
+}
+
+/// Build a `Rules` table from a list of productions, each written as `predecessor => [successor]`.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate lsystem;
+///
+/// # fn main() {
+/// let koch = rules!('F' => ['F', '+', 'F', '-', 'F', '-', 'F', '+', 'F']);
+/// assert_eq!(koch.get(&'+'), None);
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Panics if a symbol has more than one production.
+#[macro_export]
+macro_rules! rules {
+    ($($predecessor:expr => [$($successor:expr),*]),* $(,)*) => (
+        $crate::rules::Rules::new()$(.rule($predecessor, vec!($($successor),*)))*
+    );
+}
@@ -0,0 +1,149 @@
+//! Stochastic L-systems, in which a symbol may be rewritten by one of several weighted
+//! productions.
+//!
+//! Deterministic L-systems produce identical structures every time, which makes a field of plants
+//! look artificial. Section 1.7 of _The Algorithmic Beauty of Plants_ introduces variation by
+//! allowing several productions for the same predecessor, each with a probability of being
+//! chosen. The `StochasticLSystem` type implements this by letting the rule function return a
+//! list of weighted alternatives, one of which is picked for every occurrence of the symbol.
+//!
+//! Choices are made with a small pseudo-random number generator that is seeded explicitly and
+//! implemented entirely within this crate, so a given seed produces the same sequence of
+//! generations on every run and every platform. Different seeds give different plants.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::stochastic::StochasticLSystem;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Plant { F, Plus, Minus, LBrace, RBrace }
+//!
+//! use Plant::{F, Plus, Minus, LBrace, RBrace};
+//!
+//! fn plant_rule(x: Plant) -> Vec<(f64, Vec<Plant>)> {
+//!     match x {
+//!         // F -> F[+F]F[-F]F (0.33) | F[+F]F (0.33) | F[-F]F (0.34)
+//!         F => vec!((0.33, vec!(F, LBrace, Plus, F, RBrace, F, LBrace, Minus, F, RBrace, F)),
+//!                   (0.33, vec!(F, LBrace, Plus, F, RBrace, F)),
+//!                   (0.34, vec!(F, LBrace, Minus, F, RBrace, F))),
+//!         c => vec!((1.0, vec!(c)))
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let first = StochasticLSystem::new(vec!(F), plant_rule, 7).nth(4).unwrap();
+//! let again = StochasticLSystem::new(vec!(F), plant_rule, 7).nth(4).unwrap();
+//! let other = StochasticLSystem::new(vec!(F), plant_rule, 8).nth(4).unwrap();
+//!
+//! // The same seed always grows the same plant, while another seed grows a different one.
+//! assert_eq!(first, again);
+//! assert!(first != other);
+//! # }
+//! ```
+
+use std::mem;
+
+/// A SplitMix64 pseudo-random number generator.
+///
+/// This is not suitable for anything security-related, but it is fast, has good statistical
+/// properties for this purpose, and (most importantly) its output is fully specified by its seed.
+struct SplitMix64 {
+    state: u64
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed value in `[0, 1)`, built from the top 53 bits of the next output
+    /// so that the conversion is exact on every platform.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Create a stochastic Lindenmayer System defined by an axiom of type `Vec<T>`, a rule function
+/// (or closure) which maps values of type `T` to a list of weighted successors, and a seed for
+/// choosing between them.
+///
+/// Each alternative is a pair of a weight and a successor. Weights need not sum to one; they are
+/// normalised for every choice. A symbol with exactly one alternative is rewritten
+/// deterministically without consuming any randomness, so constants can be returned as
+/// `vec!((1.0, vec!(c)))` without disturbing the choices made for other symbols.
+///
+/// # Panics
+///
+/// Iterating panics if the rules return no alternatives for a symbol, if any weight is negative,
+/// infinite or `NaN`, or if the weights of its alternatives do not sum to a positive number.
+pub struct StochasticLSystem<T, F> where F: FnMut(T) -> Vec<(f64, Vec<T>)> {
+    axiom: Vec<T>,
+    rules: F,
+    rng: SplitMix64,
+    zeroth: bool
+}
+
+impl<T, F> StochasticLSystem<T, F> where F: FnMut(T) -> Vec<(f64, Vec<T>)> {
+    /// Creates a new representation of a stochastic L-system with the given axiom, production
+    /// rules and seed.
+    pub fn new(axiom: Vec<T>, rules: F, seed: u64) -> StochasticLSystem<T, F> {
+        StochasticLSystem { axiom, rules, rng: SplitMix64::new(seed), zeroth: true }
+    }
+}
+
+impl<T, F> Iterator for StochasticLSystem<T, F> where T: Clone,
+                                                      F: FnMut(T) -> Vec<(f64, Vec<T>)> {
+    type Item = Vec<T>;
+
+    /// Yield the next iteration of the L-system by rewriting each symbol of the current axiom with
+    /// one of its alternatives, chosen according to their weights.
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.zeroth {
+            self.zeroth = false;
+            return Some(self.axiom.clone())
+        }
+
+        let old_axiom = mem::take(&mut self.axiom);
+
+        for element in old_axiom {
+            let mut alternatives = (self.rules)(element);
+            if alternatives.iter().any(|&(weight, _)| !weight.is_finite() || weight < 0.0) {
+                panic!("stochastic production weights must be finite and non-negative");
+            }
+
+            let successor = match alternatives.len() {
+                0 => panic!("stochastic production has no alternatives"),
+                1 => alternatives.pop().unwrap().1,
+                _ => {
+                    let total: f64 = alternatives.iter().map(|&(weight, _)| weight).sum();
+                    if total <= 0.0 {
+                        panic!("stochastic production weights must sum to a positive number");
+                    }
+
+                    // Walk the cumulative weights until the random target is passed. Rounding
+                    // can leave the target just beyond the final sum, so fall back on the last
+                    // alternative.
+                    let mut target = self.rng.next_f64() * total;
+                    let last = alternatives.len() - 1;
+                    let index = alternatives.iter().position(|&(weight, _)| {
+                        target -= weight;
+                        target < 0.0
+                    }).unwrap_or(last);
+
+                    alternatives.swap_remove(index).1
+                }
+            };
+            self.axiom.extend(successor);
+        }
+        Some(self.axiom.clone())
+    }
+}
@@ -0,0 +1,97 @@
+//! A successor type for production rules that avoids allocating for every rewritten symbol.
+//!
+//! Returning a `Vec<T>` from a rule means one heap allocation for every symbol of every
+//! generation, even for constants that are simply rewritten into themselves. Since the successors
+//! of most L-systems are fixed strings, they can instead be written once as `static` slices and
+//! borrowed. The `Successor` type allows a rule to mix these cases freely:
+//!
+//! ```rust
+//! use lsystem::LSystem;
+//! use lsystem::successor::Successor;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Koch { F, Plus, Minus }
+//!
+//! use Koch::{F, Plus, Minus};
+//!
+//! # fn main() {
+//! let mut koch = LSystem::new(vec!(F), |x| match x {
+//!     // F -> F+F-F-F+F, without allocating.
+//!     F => Successor::Slice(&[F, Plus, F, Minus, F, Minus, F, Plus, F]),
+//!     c => Successor::One(c)
+//! });
+//!
+//! assert_eq!(koch.nth(1).unwrap(), vec!(F, Plus, F, Minus, F, Minus, F, Plus, F));
+//! # }
+//! ```
+//!
+//! The `allocations` example measures the difference this makes.
+
+use std::iter::Cloned;
+use std::{option, slice, vec};
+
+/// The result of rewriting a single symbol.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Successor<'a, T: 'a> {
+    /// Erase the symbol.
+    Empty,
+    /// Replace the symbol with exactly one symbol. Constants can return themselves this way.
+    One(T),
+    /// Replace the symbol with a borrowed list of symbols, which are cloned as they are needed.
+    Slice(&'a [T]),
+    /// Replace the symbol with an owned list of symbols.
+    Vec(Vec<T>)
+}
+
+impl<'a, T> From<&'a [T]> for Successor<'a, T> {
+    fn from(symbols: &'a [T]) -> Successor<'a, T> {
+        Successor::Slice(symbols)
+    }
+}
+
+impl<'a, T> From<Vec<T>> for Successor<'a, T> {
+    fn from(symbols: Vec<T>) -> Successor<'a, T> {
+        Successor::Vec(symbols)
+    }
+}
+
+impl<'a, T> IntoIterator for Successor<'a, T> where T: Clone {
+    type Item = T;
+    type IntoIter = IntoIter<'a, T>;
+
+    fn into_iter(self) -> IntoIter<'a, T> {
+        match self {
+            Successor::Empty => IntoIter::One(None.into_iter()),
+            Successor::One(symbol) => IntoIter::One(Some(symbol).into_iter()),
+            Successor::Slice(symbols) => IntoIter::Slice(symbols.iter().cloned()),
+            Successor::Vec(symbols) => IntoIter::Vec(symbols.into_iter())
+        }
+    }
+}
+
+/// An iterator over the symbols of a `Successor`.
+pub enum IntoIter<'a, T: 'a> {
+    #[doc(hidden)] One(option::IntoIter<T>),
+    #[doc(hidden)] Slice(Cloned<slice::Iter<'a, T>>),
+    #[doc(hidden)] Vec(vec::IntoIter<T>)
+}
+
+impl<'a, T> Iterator for IntoIter<'a, T> where T: Clone {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match *self {
+            IntoIter::One(ref mut iter) => iter.next(),
+            IntoIter::Slice(ref mut iter) => iter.next(),
+            IntoIter::Vec(ref mut iter) => iter.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            IntoIter::One(ref iter) => iter.size_hint(),
+            IntoIter::Slice(ref iter) => iter.size_hint(),
+            IntoIter::Vec(ref iter) => iter.size_hint()
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Table L-systems (TOL-systems), which hold several sets of production rules and switch between
+//! them from one generation to the next.
+//!
+//! Developmental processes often depend on the environment: a plant grows differently by day
+//! than by night, or in spring than in autumn. A table L-system captures this by keeping a
+//! number of rule "tables" and a `Schedule` that decides which one rewrites the string at each
+//! step. Within a single step every symbol is rewritten by the same table.
+//!
+//! ## An Example
+//!
+//! ```rust
+//! use lsystem::table::{Schedule, TableLSystem};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Cell { Bud, Leaf, Flower }
+//!
+//! use Cell::{Bud, Leaf, Flower};
+//!
+//! # fn main() {
+//! let mut plant = TableLSystem::new(vec!(Bud), Schedule::Cycle(vec!(0, 0, 1)))
+//!     // In the growing season, buds put out leaves.
+//!     .table(|x| match x {
+//!         Bud => vec!(Leaf, Bud),
+//!         c => vec!(c)
+//!     })
+//!     // Once a year, buds flower instead.
+//!     .table(|x| match x {
+//!         Bud => vec!(Flower),
+//!         c => vec!(c)
+//!     });
+//!
+//! assert_eq!(plant.nth(3).unwrap(), vec!(Leaf, Leaf, Flower));
+//! # }
+//! ```
+
+use std::mem;
+
+/// Decides which table of a `TableLSystem` is used to rewrite each generation.
+///
+/// Tables are identified by their index, in the order they were added. The generation index given
+/// to a schedule is that of the generation being rewritten, so the first rewrite (of the axiom)
+/// uses index zero.
+pub enum Schedule<'a> {
+    /// Use each table in the sequence once, after which the L-system is exhausted.
+    Sequence(Vec<usize>),
+    /// Repeat the sequence of tables forever. The sequence must not be empty.
+    Cycle(Vec<usize>),
+    /// Choose the table with a function of the generation index.
+    Function(Box<dyn FnMut(usize) -> usize + 'a>)
+}
+
+impl<'a> Schedule<'a> {
+    /// Creates a schedule from a function (or closure) of the generation index.
+    pub fn function<F>(f: F) -> Schedule<'a> where F: FnMut(usize) -> usize + 'a {
+        Schedule::Function(Box::new(f))
+    }
+
+    /// The table to use for rewriting the given generation, if any.
+    fn table(&mut self, generation: usize) -> Option<usize> {
+        match *self {
+            Schedule::Sequence(ref tables) => tables.get(generation).cloned(),
+            Schedule::Cycle(ref tables) => Some(tables[generation % tables.len()]),
+            Schedule::Function(ref mut f) => Some(f(generation))
+        }
+    }
+}
+
+/// Create a table Lindenmayer System defined by an axiom of type `Vec<T>`, several rule functions
+/// (or closures) which map values of type `T` to vectors of values of type `T`, and a `Schedule`
+/// for choosing between them.
+///
+/// This type behaves like `LSystem`: the first item yielded by the iterator is the axiom itself,
+/// and every subsequent item is the next generation. The iterator is only exhausted when a
+/// `Schedule::Sequence` runs out.
+///
+/// # Panics
+///
+/// Iterating panics if the schedule chooses a table that does not exist.
+pub struct TableLSystem<'a, T> {
+    axiom: Vec<T>,
+    tables: Vec<Box<dyn FnMut(T) -> Vec<T> + 'a>>,
+    schedule: Schedule<'a>,
+    generation: usize,
+    zeroth: bool
+}
+
+impl<'a, T> TableLSystem<'a, T> {
+    /// Creates a new representation of a table L-system with the given axiom and schedule, but no
+    /// tables. These are added with `table`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schedule is a `Schedule::Cycle` of no tables, which could never rewrite
+    /// anything.
+    pub fn new(axiom: Vec<T>, schedule: Schedule<'a>) -> TableLSystem<'a, T> {
+        if let Schedule::Cycle(ref tables) = schedule {
+            if tables.is_empty() {
+                panic!("a cycle of tables must not be empty");
+            }
+        }
+        TableLSystem { axiom, tables: Vec::new(), schedule, generation: 0, zeroth: true }
+    }
+
+    /// Adds a table of production rules, which will have the next available index.
+    pub fn table<F>(mut self, rules: F) -> TableLSystem<'a, T> where F: FnMut(T) -> Vec<T> + 'a {
+        self.tables.push(Box::new(rules));
+        self
+    }
+}
+
+impl<'a, T> Iterator for TableLSystem<'a, T> where T: Clone {
+    type Item = Vec<T>;
+
+    /// Yield the next iteration of the L-system by rewriting the current axiom's contents using
+    /// the table chosen by the schedule.
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.zeroth {
+            self.zeroth = false;
+            return Some(self.axiom.clone())
+        }
+
+        let index = self.schedule.table(self.generation)?;
+        let rules = match self.tables.get_mut(index) {
+            Some(rules) => rules,
+            None => panic!("schedule chose table {}, but there are only {}", index,
+                           self.tables.len())
+        };
+
+        let old_axiom = mem::take(&mut self.axiom);
+
+        for element in old_axiom {
+            self.axiom.extend(rules(element));
+        }
+        self.generation += 1;
+        Some(self.axiom.clone())
+    }
+}